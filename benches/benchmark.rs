@@ -1,48 +1,59 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-use advent_of_code_2022::solutions::{get_solver_for_day, INPUTS, N_DAYS};
+use advent_of_code_2022::inputs::{Embedded, InputSource};
+use advent_of_code_2022::solutions::registry;
 use advent_of_code_2022::Stage;
 
 fn bench_total(c: &mut Criterion) {
     c.bench_function("Full Advent", |b| {
         b.iter(|| {
-            for d in 1..=N_DAYS {
-                black_box(compute_answer(d, Stage::Easy));
-                black_box(compute_answer(d, Stage::Hard));
+            for d in registry().days() {
+                let input = Embedded.load(d.day).unwrap();
+                let parsed = d.parse(&input).unwrap();
+                for &stage in d.stages {
+                    black_box(parsed.solve(stage).unwrap());
+                }
             }
         })
     });
 }
 
-fn bench_easy(c: &mut Criterion) {
-    let mut g = c.benchmark_group("Easy");
+fn bench_parse(c: &mut Criterion) {
+    let mut g = c.benchmark_group("Parse");
 
-    for i in 1..=N_DAYS {
-        let id = format!("Day{:02}", i);
+    for d in registry().days() {
+        let input = Embedded.load(d.day).unwrap();
+        let id = format!("Day{:02}", d.day);
         g.bench_function(&*id, |b| {
             b.iter(|| {
-                black_box(compute_answer(i, Stage::Easy));
+                black_box(d.parse(&input).unwrap());
             });
         });
     }
 }
 
-fn bench_hard(c: &mut Criterion) {
-    let mut g = c.benchmark_group("Hard");
+fn bench_stage(c: &mut Criterion, stage: Stage) {
+    let mut g = c.benchmark_group(stage.to_string());
 
-    for i in 1..=N_DAYS {
-        let id = format!("Day{:02}", i);
+    for d in registry().days().filter(|d| d.is_implemented(stage)) {
+        let input = Embedded.load(d.day).unwrap();
+        let parsed = d.parse(&input).unwrap();
+        let id = format!("Day{:02}", d.day);
         g.bench_function(&*id, |b| {
             b.iter(|| {
-                black_box(compute_answer(i, Stage::Hard));
+                black_box(parsed.solve(stage).unwrap());
             });
         });
     }
 }
 
-criterion_group!(benches, bench_total, bench_easy, bench_hard);
-criterion_main!(benches);
+fn bench_easy(c: &mut Criterion) {
+    bench_stage(c, Stage::Easy);
+}
 
-fn compute_answer(day: u8, stage: Stage) -> String {
-    get_solver_for_day(day)(stage, INPUTS[(day - 1) as usize])
+fn bench_hard(c: &mut Criterion) {
+    bench_stage(c, Stage::Hard);
 }
+
+criterion_group!(benches, bench_total, bench_parse, bench_easy, bench_hard);
+criterion_main!(benches);
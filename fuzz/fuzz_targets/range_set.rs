@@ -0,0 +1,8 @@
+#![no_main]
+
+// `cargo fuzz run range_set` from the repository root
+
+use advent_of_code_2022::solutions::range_set_model::{check, Op};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|ops: Vec<Op>| check(&ops));
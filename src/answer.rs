@@ -0,0 +1,214 @@
+use std::fmt::{Display, Formatter, Write};
+
+use crate::ocr;
+use crate::solutions::AnswerType;
+
+/// The answer to one stage of a puzzle.
+///
+/// Pixel answers are displayed as the letters they spell when those can be read, and as
+/// the raw picture otherwise or when formatted with `{:#}`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    // wide enough for any of the integer types answers come in
+    Number(i128),
+    Text(String),
+    Pixels(PixelGrid),
+}
+
+impl Answer {
+    pub fn text(s: impl Into<String>) -> Answer {
+        Answer::Text(s.into())
+    }
+
+    pub fn answer_type(&self) -> AnswerType {
+        match self {
+            Answer::Number(_) => AnswerType::Number,
+            Answer::Text(_) => AnswerType::Text,
+            Answer::Pixels(_) => AnswerType::Pixels,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => f.write_str(s),
+            Answer::Pixels(grid) => match grid.read_letters() {
+                Some(letters) if !f.alternate() => f.write_str(&letters),
+                _ => write!(f, "{grid}"),
+            },
+        }
+    }
+}
+
+/// Pixel answers are equal to the text they spell, so expected answers can be written down
+/// the way they get submitted.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Number(a), Answer::Number(b)) => a == b,
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Pixels(a), Answer::Pixels(b)) => a == b,
+            (Answer::Pixels(grid), Answer::Text(text))
+            | (Answer::Text(text), Answer::Pixels(grid)) => {
+                grid.read_letters().as_ref() == Some(text)
+            }
+            _ => false,
+        }
+    }
+}
+
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(v: $t) -> Self {
+                // lossless, none of the types is wider than 64 bits
+                Answer::Number(v as i128)
+            }
+        }
+    )*};
+}
+
+impl_from_number!(i32, i64, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<PixelGrid> for Answer {
+    fn from(grid: PixelGrid) -> Self {
+        Answer::Pixels(grid)
+    }
+}
+
+/// A monochrome picture, e.g. the CRT screen of day 10.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PixelGrid {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl PixelGrid {
+    pub const LIT: char = '▉';
+    pub const DARK: char = ' ';
+
+    pub fn new(width: usize, height: usize) -> PixelGrid {
+        PixelGrid {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    /// Parses a picture drawn with `#` or `▉` for lit pixels and anything else for dark ones.
+    /// Shorter rows are padded with dark pixels.
+    pub fn from_art(art: &str) -> PixelGrid {
+        let rows: Vec<_> = art.lines().collect();
+        let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        let mut grid = PixelGrid::new(width, rows.len());
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set(x, y, matches!(c, '#' | PixelGrid::LIT));
+            }
+        }
+
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.pixels[y * self.width + x]
+    }
+
+    pub fn read_letters(&self) -> Option<String> {
+        ocr::read_letters(self)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, lit: bool) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of the grid"
+        );
+        self.pixels[y * self.width + x] = lit;
+    }
+}
+
+impl Display for PixelGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                f.write_char('\n')?;
+            }
+            for x in 0..self.width {
+                f.write_char(if self.get(x, y) {
+                    PixelGrid::LIT
+                } else {
+                    PixelGrid::DARK
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_grid_art_round_trip() {
+        let grid = PixelGrid::from_art("#..#\n####\n#");
+
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert!(grid.get(3, 0) && !grid.get(1, 0) && !grid.get(3, 2));
+        assert_eq!(grid.to_string(), "▉  ▉\n▉▉▉▉\n▉   ");
+        assert_eq!(PixelGrid::from_art(&grid.to_string()), grid);
+    }
+
+    #[test]
+    fn pixels_display_as_letters() {
+        let grid = PixelGrid::from_art(
+            "\
+#..#.####
+#..#.#...
+####.###.
+#..#.#...
+#..#.#...
+#..#.#...",
+        );
+        let answer = Answer::from(grid.clone());
+
+        assert_eq!(answer.to_string(), "HF");
+        assert_eq!(format!("{answer:#}"), grid.to_string());
+        assert_eq!(answer, Answer::text("HF"));
+        assert_ne!(answer, Answer::text("HE"));
+    }
+
+    #[test]
+    fn answer_display() {
+        assert_eq!(Answer::from(-42).to_string(), "-42");
+        assert_eq!(Answer::text("CMZ").to_string(), "CMZ");
+        assert_eq!(Answer::from(7usize).answer_type(), AnswerType::Number);
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_ne!(Answer::from(u64::MAX), Answer::from(-1));
+    }
+}
@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// A failure to solve a puzzle, usually caused by malformed input.
+///
+/// Lines and columns are 1-based and refer to the puzzle input, columns are counted in chars.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SolveError {
+    pub day: Option<u8>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl SolveError {
+    pub fn new(message: impl Display) -> SolveError {
+        SolveError {
+            day: None,
+            line: None,
+            column: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn at_line(line: usize, message: impl Display) -> SolveError {
+        SolveError {
+            line: Some(line),
+            ..SolveError::new(message)
+        }
+    }
+
+    pub fn at(line: usize, column: usize, message: impl Display) -> SolveError {
+        SolveError {
+            line: Some(line),
+            column: Some(column),
+            ..SolveError::new(message)
+        }
+    }
+
+    pub fn with_day(self, day: u8) -> SolveError {
+        SolveError {
+            day: Some(day),
+            ..self
+        }
+    }
+
+    /// Renders the error along with the offending line of `input`, compiler style.
+    pub fn diagnostic(&self, input: &str) -> String {
+        let mut result = format!("error: {}", self.message);
+
+        let location = self.location();
+        if !location.is_empty() {
+            result += &format!("\n --> {}", location.join(", "));
+        }
+
+        let Some(line_no) = self.line else {
+            return result;
+        };
+        let Some(line) = input.lines().nth(line_no.wrapping_sub(1)) else {
+            return result;
+        };
+
+        let gutter = " ".repeat(line_no.to_string().len());
+        result += &format!("\n{gutter} |\n{line_no} | {line}");
+        if let Some(column) = self.column {
+            let padding: String = line
+                .chars()
+                .take(column.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            result += &format!("\n{gutter} | {padding}^");
+        }
+
+        result
+    }
+
+    fn location(&self) -> Vec<String> {
+        [
+            ("day", self.day.map(|d| d as usize)),
+            ("line", self.line),
+            ("column", self.column),
+        ]
+        .into_iter()
+        .filter_map(|(name, v)| v.map(|v| format!("{name} {v}")))
+        .collect()
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let location = self.location();
+
+        if location.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", location.join(", "), self.message)
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostic_points_at_column() {
+        let err = SolveError::at(2, 6, "can't parse 'x'").with_day(9);
+
+        assert_eq!(err.to_string(), "day 9, line 2, column 6: can't parse 'x'");
+        assert_eq!(
+            err.diagnostic("R 4\nU 4 x\nL 3"),
+            "error: can't parse 'x'\n --> day 9, line 2, column 6\n  |\n2 | U 4 x\n  |      ^"
+        );
+    }
+
+    #[test]
+    fn diagnostic_without_location() {
+        let err = SolveError::new("the map is empty");
+        assert_eq!(err.diagnostic(""), "error: the map is empty");
+    }
+}
@@ -0,0 +1,161 @@
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{fs, path};
+
+use anyhow::{anyhow, Context};
+
+use crate::solutions::INPUTS;
+
+/// Something that can provide the puzzle input for a given day.
+pub trait InputSource {
+    fn load(&self, day: u8) -> anyhow::Result<Cow<'_, str>>;
+}
+
+/// Inputs compiled into the binary from `inputs/dayN.txt`.
+pub struct Embedded;
+
+impl InputSource for Embedded {
+    fn load(&self, day: u8) -> anyhow::Result<Cow<'_, str>> {
+        day.checked_sub(1)
+            .and_then(|idx| INPUTS.get(idx as usize))
+            .map(|input| Cow::Borrowed(*input))
+            .ok_or(anyhow!("No embedded input for day {day}"))
+    }
+}
+
+/// A single file used as the input regardless of the day.
+pub struct File {
+    path: PathBuf,
+}
+
+impl File {
+    pub fn new(path: impl Into<PathBuf>) -> File {
+        File { path: path.into() }
+    }
+}
+
+impl InputSource for File {
+    fn load(&self, _day: u8) -> anyhow::Result<Cow<'_, str>> {
+        read_file(&self.path).map(Cow::Owned)
+    }
+}
+
+/// A directory laid out like `inputs/`, i.e. containing `dayN.txt` files.
+pub struct Directory {
+    path: PathBuf,
+}
+
+impl Directory {
+    pub fn new(path: impl Into<PathBuf>) -> Directory {
+        Directory { path: path.into() }
+    }
+
+    pub fn path_for_day(&self, day: u8) -> PathBuf {
+        self.path.join(format!("day{day}.txt"))
+    }
+}
+
+impl InputSource for Directory {
+    fn load(&self, day: u8) -> anyhow::Result<Cow<'_, str>> {
+        read_file(&self.path_for_day(day)).map(Cow::Owned)
+    }
+}
+
+/// Standard input, read once on first use and shared by all days.
+#[derive(Default)]
+pub struct Stdin {
+    data: OnceLock<String>,
+}
+
+impl InputSource for Stdin {
+    fn load(&self, _day: u8) -> anyhow::Result<Cow<'_, str>> {
+        if let Some(data) = self.data.get() {
+            return Ok(Cow::Borrowed(data));
+        }
+
+        let mut data = String::new();
+        io::stdin()
+            .read_to_string(&mut data)
+            .context("Couldn't read input from stdin")?;
+
+        Ok(Cow::Borrowed(self.data.get_or_init(|| data)))
+    }
+}
+
+/// Uses `primary`, and `fallback` only for the days `primary` has no input for.
+pub struct Fallback<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P: InputSource, F: InputSource> Fallback<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Fallback { primary, fallback }
+    }
+}
+
+impl<P: InputSource, F: InputSource> InputSource for Fallback<P, F> {
+    fn load(&self, day: u8) -> anyhow::Result<Cow<'_, str>> {
+        match self.primary.load(day) {
+            Err(e) if is_not_found(&e) => self.fallback.load(day),
+            result => result,
+        }
+    }
+}
+
+impl<T: InputSource + ?Sized> InputSource for Box<T> {
+    fn load(&self, day: u8) -> anyhow::Result<Cow<'_, str>> {
+        (**self).load(day)
+    }
+}
+
+/// Picks a source from a command line style argument: `-` is stdin, a directory is
+/// treated as `inputs/`-like and anything else as a single input file.
+pub fn from_arg(arg: &str) -> Box<dyn InputSource> {
+    if arg == "-" {
+        return Box::new(Stdin::default());
+    }
+
+    let path = Path::new(arg);
+    if path.is_dir() || arg.ends_with(path::MAIN_SEPARATOR) {
+        Box::new(Directory::new(path))
+    } else {
+        Box::new(File::new(path))
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Couldn't read input from {}", path.display()))
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    e.chain()
+        .filter_map(|e| e.downcast_ref::<io::Error>())
+        .any(|e| e.kind() == io::ErrorKind::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_out_of_range() {
+        assert!(Embedded.load(0).is_err());
+        assert!(Embedded.load(INPUTS.len() as u8 + 1).is_err());
+        assert_eq!(Embedded.load(1).unwrap(), INPUTS[0]);
+    }
+
+    #[test]
+    fn fallback_on_missing_file() {
+        let source = Fallback::new(Directory::new("does/not/exist"), Embedded);
+        assert_eq!(source.load(3).unwrap(), INPUTS[2]);
+    }
+
+    #[test]
+    fn directory_matches_embedded() {
+        let source = Directory::new(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs"));
+        assert_eq!(source.load(7).unwrap(), Embedded.load(7).unwrap());
+    }
+}
@@ -1,9 +1,14 @@
+use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 
+pub mod answer;
+pub mod error;
+pub mod inputs;
+pub mod ocr;
 pub mod solutions;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Stage {
     Easy = 0,
     Hard = 1,
@@ -16,21 +21,49 @@ impl Display for Stage {
 }
 
 impl Stage {
-    fn is_hard(&self) -> bool {
+    pub const ALL: [Stage; 2] = [Stage::Easy, Stage::Hard];
+
+    /// Everything `from_str` accepts for the stage, the canonical name first.
+    pub fn spellings(&self) -> &'static [&'static str] {
         match self {
-            Stage::Hard => true,
-            Stage::Easy => false,
+            Stage::Easy => &["easy", "-", "false"],
+            Stage::Hard => &["hard", "+", "true"],
         }
     }
 }
 
 impl FromStr for Stage {
-    type Err = anyhow::Error;
+    type Err = ParseStageError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "+" | "hard" | "true" => Ok(Stage::Hard),
-            &_ => Ok(Stage::Easy),
-        }
+        Stage::ALL
+            .into_iter()
+            .find(|stage| stage.spellings().contains(&s))
+            .ok_or_else(|| ParseStageError(s.to_string()))
     }
 }
+
+/// A string that doesn't name a stage.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseStageError(pub String);
+
+impl Display for ParseStageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let accepted: Vec<String> = Stage::ALL
+            .iter()
+            .map(|stage| {
+                let (name, aliases) = stage.spellings().split_first().unwrap();
+                format!("'{name}' (or {})", aliases.join(", "))
+            })
+            .collect();
+
+        write!(
+            f,
+            "unknown stage '{}', expected {}",
+            self.0,
+            accepted.join(" or ")
+        )
+    }
+}
+
+impl Error for ParseStageError {}
@@ -1,37 +1,461 @@
-use std::env;
-use std::error::Error;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-use anyhow::anyhow;
-use advent_of_code_2022;
-use advent_of_code_2022::solutions::INPUTS;
+use clap::{Parser, Subcommand, ValueEnum};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = parse_args()?;
-    println!("day = {}, stage = {:?}", args.day, args.stage);
+use advent_of_code_2022::answer::Answer;
+use advent_of_code_2022::error::SolveError;
+use advent_of_code_2022::inputs::{self, Embedded, Fallback, InputSource};
+use advent_of_code_2022::solutions::{registry, DayInfo, Params, ParsedInput};
+use advent_of_code_2022::Stage;
 
-    let solver = advent_of_code_2022::solutions::get_solver_for_day(args.day);
-    let result = solver(args.stage, INPUTS[(args.day - 1) as usize]);
+#[derive(Parser)]
+#[command(about = "Solutions for Advent of Code 2022")]
+struct Cli {
+    /// Where to read inputs from: a file, a directory of dayN.txt files or '-' for stdin
+    #[arg(long, short, global = true, default_value = "inputs/")]
+    input: String,
 
-    println!("{}", result);
+    /// Use the inputs compiled into the binary for the days the input source has none for
+    #[arg(long, global = true)]
+    embedded: bool,
 
-    Ok(())
+    /// How to print the results
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Human)]
+    format: Format,
+
+    /// Print pictures (day 10) as they are drawn instead of the letters read from them
+    #[arg(long, global = true)]
+    render_pixels: bool,
+
+    /// Also show how each answer comes about, for the days that can (the routes of day 12,
+    /// the valve timelines of day 16)
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// Change a setting of the puzzle, e.g. '--param start=BB' for day 16. Can be repeated
+    #[arg(long = "param", global = true, value_name = "NAME=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve both stages of a day, or just one of them
+    Run {
+        day: u8,
+
+        /// The stage to solve, or 'both'
+        #[arg(default_value = "both", value_parser = parse_stages)]
+        stage: Stages,
+    },
+    /// Solve every available day
+    RunAll,
+    /// List the available days
+    List,
+    /// Solve and time each stage of a day, or of every day
+    Time {
+        day: Option<u8>,
+
+        /// Number of runs to take the best time of
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+    },
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Stages {
+    One(Stage),
+    Both,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, ValueEnum)]
+enum Format {
+    Human,
+    Json,
+    Csv,
+}
+
+struct Outcome<'a> {
+    day: &'a DayInfo,
+    stage: Stage,
+    result: Result<Answer, Failure>,
+    time: Option<Duration>,
+    explanation: Option<String>,
+}
+
+struct Failure {
+    error: SolveError,
+    diagnostic: String,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let source = inputs::from_arg(&cli.input);
+    let source: Box<dyn InputSource> = if cli.embedded {
+        Box::new(Fallback::new(source, Embedded))
+    } else {
+        source
+    };
+
+    let (days, stage, runs) = match &cli.command {
+        Command::List => {
+            print_days(cli.format);
+            return ExitCode::SUCCESS;
+        }
+        Command::Run { day, stage } => (vec![*day], *stage, None),
+        Command::RunAll => (
+            registry().days().map(|d| d.day).collect(),
+            Stages::Both,
+            None,
+        ),
+        Command::Time { day, runs } => match day {
+            Some(day) => (vec![*day], Stages::Both, Some(*runs)),
+            None => (
+                registry().days().map(|d| d.day).collect(),
+                Stages::Both,
+                Some(*runs),
+            ),
+        },
+    };
+
+    let mut params = Params::new();
+    for (name, value) in &cli.params {
+        params.set(name, value);
+    }
+
+    let mut outcomes = Vec::new();
+    for day in days {
+        match registry().get(day) {
+            Ok(day) => outcomes.extend(solve(day, stage, &*source, &params, runs, cli.explain)),
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let single_answer = matches!(
+        cli.command,
+        Command::Run {
+            stage: Stages::One(_),
+            ..
+        }
+    );
+    print_outcomes(&outcomes, cli.format, single_answer, cli.render_pixels);
+
+    if outcomes.iter().all(|o| o.result.is_ok()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn parse_stages(s: &str) -> Result<Stages, String> {
+    match s {
+        "both" => Ok(Stages::Both),
+        s => s
+            .parse()
+            .map(Stages::One)
+            .map_err(|e| format!("{e} or 'both'")),
+    }
+}
+
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected NAME=VALUE, got '{s}'"))
+}
+
+fn solve<'a>(
+    day: &'a DayInfo,
+    stage: Stages,
+    source: &dyn InputSource,
+    params: &Params,
+    runs: Option<u32>,
+    explain: bool,
+) -> Vec<Outcome<'a>> {
+    let stages: Vec<Stage> = match stage {
+        Stages::One(stage) => vec![stage],
+        Stages::Both => day.stages.to_vec(),
+    };
+
+    let input = source.load(day.day);
+    let solve_stage = |stage: Stage, input: &str| day.parse_with(input, params)?.solve(stage);
+
+    let explain_parsed = |parsed: &ParsedInput, stage: Stage, input: &str| {
+        if !explain {
+            return None;
+        }
+        parsed
+            .explain(stage)
+            .unwrap_or_else(|error| Some(error.diagnostic(input)))
+    };
+    // explaining is done apart from solving, so that it doesn't count towards the times
+    let explanation = |stage: Stage, input: &str| {
+        if !explain {
+            return None;
+        }
+        match day.parse_with(input, params) {
+            Ok(parsed) => explain_parsed(&parsed, stage, input),
+            Err(error) => Some(error.diagnostic(input)),
+        }
+    };
+
+    // Without timing there's no need to tell the stages apart, so let the day share its
+    // parsing between them. If that fails, the stages are solved one by one below so each
+    // gets its own error.
+    if let (Ok(input), Stages::Both, None) = (&input, stage, runs) {
+        if day.stages == Stage::ALL {
+            let both = day.parse_with(input, params).and_then(|parsed| {
+                let answers = [parsed.solve(Stage::Easy)?, parsed.solve(Stage::Hard)?];
+                Ok((parsed, answers))
+            });
+            if let Ok((parsed, answers)) = both {
+                return Stage::ALL
+                    .into_iter()
+                    .zip(answers)
+                    .map(|(stage, answer)| Outcome {
+                        day,
+                        stage,
+                        result: Ok(answer),
+                        time: None,
+                        explanation: explain_parsed(&parsed, stage, input),
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    stages
+        .into_iter()
+        .map(|stage| {
+            let input = match &input {
+                Ok(input) => input,
+                Err(e) => {
+                    let error = SolveError::new(format!("{e:#}")).with_day(day.day);
+                    return Outcome {
+                        day,
+                        stage,
+                        result: Err(Failure {
+                            diagnostic: error.diagnostic(""),
+                            error,
+                        }),
+                        time: None,
+                        explanation: None,
+                    };
+                }
+            };
+
+            let (result, time) = match runs {
+                None => (solve_stage(stage, input), None),
+                Some(runs) => {
+                    let mut best = Duration::MAX;
+                    let mut result = None;
+                    for _ in 0..runs {
+                        let start = Instant::now();
+                        result = Some(solve_stage(stage, input));
+                        best = best.min(start.elapsed());
+                    }
+                    (result.unwrap(), Some(best))
+                }
+            };
+
+            let explanation = result.is_ok().then(|| explanation(stage, input)).flatten();
+            Outcome {
+                day,
+                stage,
+                result: result.map_err(|error| Failure {
+                    diagnostic: error.diagnostic(input),
+                    error,
+                }),
+                time,
+                explanation,
+            }
+        })
+        .collect()
+}
+
+fn print_outcomes(outcomes: &[Outcome], format: Format, single_answer: bool, render_pixels: bool) {
+    let render = |answer: &Answer| {
+        if render_pixels {
+            format!("{answer:#}")
+        } else {
+            answer.to_string()
+        }
+    };
+
+    match format {
+        Format::Human => {
+            for o in outcomes {
+                let mut line = if single_answer {
+                    String::new()
+                } else {
+                    format!("Day {:02}, {}: ", o.day.day, stage_name(o.stage))
+                };
+
+                match &o.result {
+                    Ok(answer) => {
+                        let answer = render(answer);
+                        if answer.contains('\n') && !line.is_empty() {
+                            line.push('\n');
+                        }
+                        line += &answer;
+                    }
+                    Err(failure) => {
+                        eprintln!("{}", failure.diagnostic);
+                        if single_answer {
+                            continue;
+                        }
+                        line += "failed";
+                    }
+                }
+
+                if let Some(time) = o.time {
+                    line += &format!(" ({time:.2?})");
+                }
+                println!("{line}");
+                if let Some(explanation) = &o.explanation {
+                    println!("{}", explanation.trim_end());
+                }
+            }
+        }
+        Format::Json => {
+            let records: Vec<String> = outcomes
+                .iter()
+                .map(|o| {
+                    let mut fields = vec![
+                        format!("\"day\":{}", o.day.day),
+                        format!("\"title\":{}", json_string(o.day.title)),
+                        format!("\"stage\":\"{}\"", stage_name(o.stage)),
+                    ];
+                    match &o.result {
+                        Ok(Answer::Number(n)) => fields.push(format!("\"answer\":{n}")),
+                        Ok(answer) => {
+                            fields.push(format!("\"answer\":{}", json_string(&render(answer))))
+                        }
+                        Err(failure) => fields.push(format!(
+                            "\"error\":{}",
+                            json_string(&failure.error.to_string())
+                        )),
+                    }
+                    if let Some(time) = o.time {
+                        fields.push(format!("\"time_us\":{}", time.as_micros()));
+                    }
+                    if let Some(explanation) = &o.explanation {
+                        fields.push(format!("\"explanation\":{}", json_string(explanation)));
+                    }
+                    format!("{{{}}}", fields.join(","))
+                })
+                .collect();
+
+            println!("[{}]", records.join(",\n "));
+        }
+        Format::Csv => {
+            println!("day,title,stage,answer,error,time_us");
+            for o in outcomes {
+                let (answer, error) = match &o.result {
+                    Ok(answer) => (render(answer), String::new()),
+                    Err(failure) => (String::new(), failure.error.to_string()),
+                };
+                let time = o
+                    .time
+                    .map(|t| t.as_micros().to_string())
+                    .unwrap_or_default();
+
+                println!(
+                    "{},{},{},{},{},{}",
+                    o.day.day,
+                    csv_field(o.day.title),
+                    stage_name(o.stage),
+                    csv_field(&answer),
+                    csv_field(&error),
+                    time
+                );
+            }
+        }
+    }
 }
 
-struct Args {
-    day: u8,
-    stage: advent_of_code_2022::Stage,
+fn print_days(format: Format) {
+    let answer_types = |day: &DayInfo| {
+        Stage::ALL.map(|stage| match day.is_implemented(stage) {
+            true => format!("{:?}", day.answer_type(stage)).to_lowercase(),
+            false => String::new(),
+        })
+    };
+
+    match format {
+        Format::Human => {
+            for day in registry().days() {
+                let [easy, hard] = answer_types(day);
+                println!(
+                    "{:2}  {:<26}  easy: {:<7} hard: {}",
+                    day.day, day.title, easy, hard
+                );
+            }
+        }
+        Format::Json => {
+            let records: Vec<String> = registry()
+                .days()
+                .map(|day| {
+                    let [easy, hard] = answer_types(day).map(|t| match t.is_empty() {
+                        true => "null".to_string(),
+                        false => json_string(&t),
+                    });
+                    format!(
+                        "{{\"day\":{},\"title\":{},\"easy\":{},\"hard\":{}}}",
+                        day.day,
+                        json_string(day.title),
+                        easy,
+                        hard
+                    )
+                })
+                .collect();
+
+            println!("[{}]", records.join(",\n "));
+        }
+        Format::Csv => {
+            println!("day,title,easy,hard");
+            for day in registry().days() {
+                let [easy, hard] = answer_types(day);
+                println!("{},{},{},{}", day.day, csv_field(day.title), easy, hard);
+            }
+        }
+    }
 }
 
-fn parse_args() -> Result<Args, Box<dyn Error>> {
-    if env::args().count() < 2 {
-        let err = anyhow!("Day argument is required");
-        return Err(err.into());
+fn stage_name(stage: Stage) -> &'static str {
+    match stage {
+        Stage::Easy => "easy",
+        Stage::Hard => "hard",
     }
+}
 
-    let mut args = env::args().skip(1);
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            c if c.is_control() => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
 
-    let day: u8 = args.next().unwrap().parse()?;
-    let stage: advent_of_code_2022::Stage = args.next().or(Some("easy".into())).unwrap().parse()?;
+    result
+}
 
-    Ok(Args { day, stage })
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
 }
@@ -0,0 +1,99 @@
+use crate::answer::PixelGrid;
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+
+// glyphs are separated by a single dark column
+const GLYPH_PITCH: usize = GLYPH_WIDTH + 1;
+
+// The 4x6 font used by the puzzles. Not every letter ever shows up, so some are missing.
+static FONT: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn on `grid`, `None` if anything on it isn't a known letter.
+pub fn read_letters(grid: &PixelGrid) -> Option<String> {
+    if grid.height() != GLYPH_HEIGHT {
+        return None;
+    }
+
+    let mut result = String::new();
+
+    for x0 in (0..grid.width()).step_by(GLYPH_PITCH) {
+        let is_lit = |dx: usize, y: usize| grid.get(x0 + dx, y);
+
+        if (0..GLYPH_WIDTH).all(|dx| (0..GLYPH_HEIGHT).all(|y| !is_lit(dx, y))) {
+            // trailing blank space
+            if (x0..grid.width()).all(|x| (0..GLYPH_HEIGHT).all(|y| !grid.get(x, y))) {
+                break;
+            }
+            return None;
+        }
+        if (0..GLYPH_HEIGHT).any(|y| is_lit(GLYPH_WIDTH, y)) {
+            return None;
+        }
+
+        let (letter, _) = FONT.iter().find(|(_, glyph)| {
+            glyph.iter().enumerate().all(|(y, row)| {
+                row.bytes()
+                    .enumerate()
+                    .all(|(dx, c)| (c == b'#') == is_lit(dx, y))
+            })
+        })?;
+        result.push(*letter);
+    }
+
+    (!result.is_empty()).then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(letters: &str) -> PixelGrid {
+        let rows: Vec<String> = (0..GLYPH_HEIGHT)
+            .map(|y| {
+                letters
+                    .chars()
+                    .map(|l| FONT.iter().find(|(c, _)| *c == l).unwrap().1[y])
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect();
+
+        PixelGrid::from_art(&rows.join("\n"))
+    }
+
+    #[test]
+    fn reads_every_letter() {
+        let alphabet: String = FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(
+            read_letters(&render(&alphabet)).as_deref(),
+            Some(&*alphabet)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_glyphs() {
+        assert_eq!(read_letters(&PixelGrid::from_art("#\n#\n#\n#\n#\n#")), None);
+        assert_eq!(read_letters(&PixelGrid::from_art("####")), None);
+        assert_eq!(read_letters(&PixelGrid::new(40, 6)), None);
+    }
+}
@@ -1,23 +1,25 @@
-use seq_macro::seq;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::AnswerType::{Number, Pixels, Text};
 use crate::Stage;
+use seq_macro::seq;
+
+pub mod common;
+
+/// Model checks of the solvers' building blocks, for the fuzz targets in `fuzz/`.
+#[cfg(fuzzing)]
+pub use common::range_set_model;
 
-mod common;
+seq!(N in 1..=25 {
 
-seq!(N in 1..=16 {
-    
     #(
         mod day~N;
     )*
 
-    pub fn get_solver_for_day(day: u8) -> SolverFunc {
-        match day {
-            #(
-            N => day~N::solve,
-            )*
-            _ => panic!("Invalid day"),
-        }
-    }
-    
     pub static INPUTS: &[&str] = &[
         #(
         include_str!(concat!("../inputs/day", stringify!(N), ".txt")),
@@ -25,6 +27,270 @@ seq!(N in 1..=16 {
     ];
 });
 
-pub type SolverFunc = fn(stage: Stage, input: &str) -> String;
+/// A puzzle solved in two phases: the input is parsed once, then each stage is solved from
+/// the parsed form.
+pub trait Solution {
+    type Parsed<'a>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, SolveError>;
+    fn part1(parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError>;
+    fn part2(parsed: &Self::Parsed<'_>) -> Result<Answer, SolveError>;
+
+    /// Applies the settings given for the day to its parsed input. Days that have none reject
+    /// any parameter.
+    fn configure(_parsed: &mut Self::Parsed<'_>, params: &Params) -> Result<(), SolveError> {
+        params.check_names(&[])
+    }
+
+    /// Shows how the answer to `stage` comes about, as text meant to be checked by hand. Most
+    /// days have nothing to show.
+    fn explain(_parsed: &Self::Parsed<'_>, _stage: Stage) -> Result<Option<String>, SolveError> {
+        Ok(None)
+    }
+}
+
+/// Settings that a day's puzzle fixes but could just as well be different, like where to
+/// start from or how long to go for, given as named values.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Params {
+    values: Vec<(String, String)>,
+}
+
+impl Params {
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    /// Sets `name` to `value`, replacing any value it had.
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.values.retain(|(n, _)| *n != name);
+        self.values.push((name, value.into()));
+    }
+
+    /// The value of `name` parsed as a `T`, `default` if it isn't set.
+    pub fn get<T: FromStr>(&self, name: &str, default: T) -> Result<T, SolveError>
+    where
+        T::Err: Display,
+    {
+        match self.values.iter().find(|(n, _)| n == name) {
+            None => Ok(default),
+            Some((_, value)) => value.parse().map_err(|e| {
+                SolveError::new(format_args!("can't parse parameter {name}='{value}': {e}"))
+            }),
+        }
+    }
+
+    /// Fails on the first parameter that isn't one of `known`.
+    pub fn check_names(&self, known: &[&str]) -> Result<(), SolveError> {
+        let Some((name, _)) = self
+            .values
+            .iter()
+            .find(|(n, _)| !known.contains(&n.as_str()))
+        else {
+            return Ok(());
+        };
+
+        Err(SolveError::new(match known {
+            [] => format!("unknown parameter '{name}', the day takes none"),
+            _ => format!(
+                "unknown parameter '{name}', expected one of {}",
+                known.join(", ")
+            ),
+        }))
+    }
+}
+
+/// What kind of value a stage's answer is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AnswerType {
+    Number,
+    Text,
+    Pixels,
+}
+
+pub struct DayInfo {
+    pub day: u8,
+    pub title: &'static str,
+    pub stages: &'static [Stage],
+    answer_types: [AnswerType; 2],
+    parser: ParseFunc,
+}
+
+type ParseFunc = for<'a> fn(&'a str, &Params) -> Result<Box<dyn Prepared + 'a>, SolveError>;
+
+impl DayInfo {
+    const fn new<S: Solution + 'static>(
+        day: u8,
+        title: &'static str,
+        answer_types: [AnswerType; 2],
+    ) -> Self {
+        DayInfo {
+            day,
+            title,
+            stages: &[Stage::Easy, Stage::Hard],
+            answer_types,
+            parser: prepare::<S>,
+        }
+    }
+
+    const fn with_stages(self, stages: &'static [Stage]) -> Self {
+        DayInfo { stages, ..self }
+    }
+
+    pub fn is_implemented(&self, stage: Stage) -> bool {
+        self.stages.contains(&stage)
+    }
+
+    pub fn answer_type(&self, stage: Stage) -> AnswerType {
+        self.answer_types[stage as usize]
+    }
+
+    /// Runs the parsing phase, the result can then solve any stage without parsing again.
+    pub fn parse<'a>(&self, input: &'a str) -> Result<ParsedInput<'a>, SolveError> {
+        self.parse_with(input, &Params::new())
+    }
+
+    /// Like `parse`, with `params` changing the settings the puzzle gives.
+    pub fn parse_with<'a>(
+        &self,
+        input: &'a str,
+        params: &Params,
+    ) -> Result<ParsedInput<'a>, SolveError> {
+        Ok(ParsedInput {
+            day: self.day,
+            prepared: (self.parser)(input, params).map_err(|e| e.with_day(self.day))?,
+        })
+    }
+
+    pub fn solve(&self, stage: Stage, input: &str) -> Result<Answer, SolveError> {
+        self.parse(input)?.solve(stage)
+    }
+
+    /// Solves both stages from a single parse of the input, answers are indexed by stage.
+    pub fn solve_both(&self, input: &str) -> Result<[Answer; 2], SolveError> {
+        let parsed = self.parse(input)?;
+        Ok([parsed.solve(Stage::Easy)?, parsed.solve(Stage::Hard)?])
+    }
+}
+
+/// The input of a day after its parsing phase.
+pub struct ParsedInput<'a> {
+    day: u8,
+    prepared: Box<dyn Prepared + 'a>,
+}
+
+impl ParsedInput<'_> {
+    pub fn solve(&self, stage: Stage) -> Result<Answer, SolveError> {
+        self.prepared.solve(stage).map_err(|e| e.with_day(self.day))
+    }
+
+    /// How the answer to `stage` comes about, `None` for the days that can't show it.
+    pub fn explain(&self, stage: Stage) -> Result<Option<String>, SolveError> {
+        self.prepared
+            .explain(stage)
+            .map_err(|e| e.with_day(self.day))
+    }
+}
+
+// Lets the registry hold every day's parsed input behind the same type.
+trait Prepared {
+    fn solve(&self, stage: Stage) -> Result<Answer, SolveError>;
+    fn explain(&self, stage: Stage) -> Result<Option<String>, SolveError>;
+}
+
+struct PreparedInput<'a, S: Solution>(S::Parsed<'a>);
+
+impl<'a, S: Solution> Prepared for PreparedInput<'a, S> {
+    fn solve(&self, stage: Stage) -> Result<Answer, SolveError> {
+        match stage {
+            Stage::Easy => S::part1(&self.0),
+            Stage::Hard => S::part2(&self.0),
+        }
+    }
+
+    fn explain(&self, stage: Stage) -> Result<Option<String>, SolveError> {
+        S::explain(&self.0, stage)
+    }
+}
+
+fn prepare<'a, S: Solution + 'static>(
+    input: &'a str,
+    params: &Params,
+) -> Result<Box<dyn Prepared + 'a>, SolveError> {
+    let mut parsed = S::parse(input)?;
+    S::configure(&mut parsed, params)?;
+
+    Ok(Box::new(PreparedInput::<S>(parsed)))
+}
+
+pub struct Registry {
+    days: &'static [DayInfo],
+}
+
+impl Registry {
+    pub fn days(&self) -> impl Iterator<Item = &DayInfo> {
+        self.days.iter()
+    }
+
+    pub fn get(&self, day: u8) -> Result<&DayInfo, UnknownDay> {
+        self.days
+            .iter()
+            .find(|info| info.day == day)
+            .ok_or(UnknownDay(day))
+    }
+}
+
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+static REGISTRY: Registry = Registry {
+    days: &[
+        DayInfo::new::<day1::Solver>(1, "Calorie Counting", [Number, Number]),
+        DayInfo::new::<day2::Solver>(2, "Rock Paper Scissors", [Number, Number]),
+        DayInfo::new::<day3::Solver>(3, "Rucksack Reorganization", [Number, Number]),
+        DayInfo::new::<day4::Solver>(4, "Camp Cleanup", [Number, Number]),
+        DayInfo::new::<day5::Solver>(5, "Supply Stacks", [Text, Text]),
+        DayInfo::new::<day6::Solver>(6, "Tuning Trouble", [Number, Number]),
+        DayInfo::new::<day7::Solver>(7, "No Space Left On Device", [Number, Number]),
+        DayInfo::new::<day8::Solver>(8, "Treetop Tree House", [Number, Number]),
+        DayInfo::new::<day9::Solver>(9, "Rope Bridge", [Number, Number]),
+        DayInfo::new::<day10::Solver>(10, "Cathode-Ray Tube", [Number, Pixels]),
+        DayInfo::new::<day11::Solver>(11, "Monkey in the Middle", [Number, Number]),
+        DayInfo::new::<day12::Solver>(12, "Hill Climbing Algorithm", [Number, Number]),
+        DayInfo::new::<day13::Solver>(13, "Distress Signal", [Number, Number]),
+        DayInfo::new::<day14::Solver>(14, "Regolith Reservoir", [Number, Number]),
+        DayInfo::new::<day15::Solver>(15, "Beacon Exclusion Zone", [Number, Number]),
+        DayInfo::new::<day16::Solver>(16, "Proboscidea Volcanium", [Number, Number]),
+        DayInfo::new::<day17::Solver>(17, "Pyroclastic Flow", [Number, Number]),
+        DayInfo::new::<day18::Solver>(18, "Boiling Boulders", [Number, Number]),
+        DayInfo::new::<day19::Solver>(19, "Not Enough Minerals", [Number, Number]),
+        DayInfo::new::<day20::Solver>(20, "Grove Positioning System", [Number, Number]),
+        DayInfo::new::<day21::Solver>(21, "Monkey Math", [Number, Number]),
+        DayInfo::new::<day22::Solver>(22, "Monkey Map", [Number, Number]),
+        DayInfo::new::<day23::Solver>(23, "Unstable Diffusion", [Number, Number]),
+        DayInfo::new::<day24::Solver>(24, "Blizzard Basin", [Number, Number]),
+        // the last day only has one puzzle
+        DayInfo::new::<day25::Solver>(25, "Full of Hot Air", [Text, Text])
+            .with_stages(&[Stage::Easy]),
+    ],
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct UnknownDay(pub u8);
+
+impl Display for UnknownDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut days = registry().days().map(|d| d.day);
+        let first = days.next().unwrap_or_default();
+        let last = days.last().unwrap_or(first);
+        write!(
+            f,
+            "Day {} is not solved, available days are {}..={}",
+            self.0, first, last
+        )
+    }
+}
 
-pub static N_DAYS: u8 = INPUTS.len() as u8;
+impl Error for UnknownDay {}
@@ -1,59 +1,107 @@
+pub mod grid;
+pub mod parse;
+pub mod point;
+#[cfg(any(test, fuzzing))]
+pub mod range_set_model;
+pub mod region;
+pub mod search;
+
 use crate::solutions::common::RangeSetInsertPosition::{Insert, Replace};
+use num_traits::PrimInt;
 use std::cmp::{max, min};
 use std::ops::RangeInclusive;
 
+/// A non-empty inclusive range of integers, `start() <= end()` always holds.
 #[derive(Ord, PartialOrd, Eq, PartialEq, Default, Debug, Hash, Clone, Copy)]
-pub struct MyRange {
-    pub(crate) from: isize,
-    pub(crate) to: isize,
+pub struct MyRange<T = isize> {
+    from: T,
+    to: T,
 }
 
-impl MyRange {
-    pub(crate) fn new(from: isize, to: isize) -> Self {
-        Self { from, to }
+impl<T: PrimInt> MyRange<T> {
+    /// Panics if `from > to`, see `try_new` for bounds that come from the input.
+    pub(crate) fn new(from: T, to: T) -> Self {
+        Self::try_new(from, to).expect("the range ends before it starts")
+    }
+
+    pub(crate) fn try_new(from: T, to: T) -> Option<Self> {
+        (from <= to).then_some(Self { from, to })
     }
 
+    pub(crate) fn start(&self) -> T {
+        self.from
+    }
+
+    pub(crate) fn end(&self) -> T {
+        self.to
+    }
+
+    /// The number of values in the range, saturating at `usize::MAX`.
     pub(crate) fn count(&self) -> usize {
-        (self.to - self.from + 1) as usize
+        self.to
+            .checked_sub(&self.from)
+            .and_then(|diff| diff.to_usize())
+            .map_or(usize::MAX, |diff| diff.saturating_add(1))
     }
 
-    pub(crate) fn contains(&self, v: isize) -> bool {
+    pub(crate) fn contains(&self, v: T) -> bool {
         self.from <= v && self.to >= v
     }
+
+    pub(crate) fn contains_range(&self, other: &Self) -> bool {
+        self.from <= other.from && self.to >= other.to
+    }
+
+    pub(crate) fn intersection(&self, other: &Self) -> Option<Self> {
+        Self::try_new(max(self.from, other.from), min(self.to, other.to))
+    }
 }
 
-impl From<(isize, isize)> for MyRange {
-    fn from((from, to): (isize, isize)) -> Self {
-        Self { from, to }
+/// The range between two bounds given in either order.
+impl<T: PrimInt> From<(T, T)> for MyRange<T> {
+    fn from((a, b): (T, T)) -> Self {
+        Self {
+            from: min(a, b),
+            to: max(a, b),
+        }
     }
 }
 
-impl From<isize> for MyRange {
-    fn from(v: isize) -> Self {
+impl<T: Copy> From<T> for MyRange<T> {
+    fn from(v: T) -> Self {
         Self { from: v, to: v }
     }
 }
 
-pub struct RangeSet {
-    ranges: Vec<MyRange>,
+/// A set of integers stored as sorted, disjoint and non-touching ranges.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RangeSet<T = isize> {
+    ranges: Vec<MyRange<T>>,
 }
 
-impl RangeSet {
+impl<T: PrimInt> RangeSet<T> {
     pub(crate) fn clear(&mut self) {
         self.ranges.clear()
     }
 
-    pub fn with_capacity(capacity: usize) -> RangeSet {
+    pub fn with_capacity(capacity: usize) -> RangeSet<T> {
         RangeSet {
             ranges: Vec::with_capacity(capacity),
         }
     }
 
-    pub fn ranges(&self) -> &[MyRange] {
+    pub fn ranges(&self) -> &[MyRange<T>] {
         &self.ranges
     }
 
-    pub fn contains(&self, value: isize) -> bool {
+    /// The number of values in the set, saturating at `usize::MAX`.
+    pub fn count(&self) -> usize {
+        self.ranges
+            .iter()
+            .fold(0usize, |total, r| total.saturating_add(r.count()))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
         let pos = self.ranges.binary_search(&value.into());
 
         match pos {
@@ -65,12 +113,7 @@ impl RangeSet {
         }
     }
 
-    pub fn insert(&mut self, range: MyRange) {
-        if range.from > range.to {
-            self.insert(MyRange::new(range.to, range.from));
-            return;
-        }
-
+    pub fn insert(&mut self, range: MyRange<T>) {
         match self.get_insert_position(range, true) {
             Replace(replace_range) => {
                 let upper_bound = match self.ranges.get(*replace_range.end()) {
@@ -89,16 +132,105 @@ impl RangeSet {
             Insert(pos) => self.ranges.insert(pos, range),
         }
     }
+
+    pub fn remove(&mut self, range: MyRange<T>) {
+        let first = self.ranges.partition_point(|r| r.to < range.from);
+        let last = self.ranges.partition_point(|r| r.from <= range.to);
+        if first >= last {
+            return;
+        }
+
+        // only the outermost of the overlapping ranges can stick out of the removed one
+        let mut leftovers = Vec::with_capacity(2);
+        let (head, tail) = (self.ranges[first], self.ranges[last - 1]);
+        if head.from < range.from {
+            leftovers.push(MyRange::new(head.from, range.from - T::one()));
+        }
+        if tail.to > range.to {
+            leftovers.push(MyRange::new(range.to + T::one(), tail.to));
+        }
+
+        self.ranges.splice(first..last, leftovers);
+    }
+
+    /// The ranges within `bounds` that are not covered by the set, in ascending order.
+    pub fn gaps(&self, bounds: MyRange<T>) -> impl Iterator<Item = MyRange<T>> + '_ {
+        let first = self.ranges.partition_point(|r| r.to < bounds.from);
+        let mut next_from = Some(bounds.from);
+
+        self.ranges[first..]
+            .iter()
+            .take_while(move |r| r.from <= bounds.to)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .filter_map(move |r| {
+                let from = next_from?;
+                match r {
+                    Some(r) => {
+                        next_from = r.to.checked_add(&T::one()).filter(|&f| f <= bounds.to);
+                        (r.from > from).then(|| MyRange::new(from, r.from - T::one()))
+                    }
+                    None => {
+                        next_from = None;
+                        Some(MyRange::new(from, bounds.to))
+                    }
+                }
+            })
+    }
 }
 
-impl RangeSet {
+/// Set algebra, for the solvers that combine several sets.
+impl<T: PrimInt> RangeSet<T> {
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        for &range in &other.ranges {
+            result.insert(range);
+        }
+
+        result
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = RangeSet::with_capacity(max(self.ranges.len(), other.ranges.len()));
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            if let Some(common) = a.intersection(b) {
+                result.ranges.push(common);
+            }
+            if a.to < b.to {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        result
+    }
+
+    /// The values within `bounds` that are not in the set.
+    pub fn complement(&self, bounds: MyRange<T>) -> RangeSet<T> {
+        RangeSet {
+            ranges: self.gaps(bounds).collect(),
+        }
+    }
+}
+
+impl<T: PrimInt> RangeSet<T> {
     fn get_insert_position(
         &self,
-        range: MyRange,
+        range: MyRange<T>,
         include_touching: bool,
     ) -> RangeSetInsertPosition {
         let range = if include_touching {
-            MyRange::new(range.from - 1, range.to + 1)
+            MyRange::new(
+                range.from.saturating_sub(T::one()),
+                range.to.saturating_add(T::one()),
+            )
         } else {
             range
         };
@@ -119,7 +251,7 @@ impl RangeSet {
 
         return Replace(idx_left..=idx_right);
 
-        fn get_index(rs: &RangeSet, value: isize) -> (usize, bool, bool) {
+        fn get_index<T: PrimInt>(rs: &RangeSet<T>, value: T) -> (usize, bool, bool) {
             let idx_res = rs.ranges.binary_search(&value.into());
             match idx_res {
                 Ok(pos) => (pos, false, true),
@@ -155,6 +287,16 @@ enum RangeSetInsertPosition {
 mod tests {
     use super::*;
 
+    #[test]
+    fn range_bounds() {
+        assert_eq!(MyRange::try_new(3, 2), None);
+        assert_eq!(MyRange::from((3, -2)), MyRange::new(-2, 3));
+        assert_eq!(MyRange::new(-2, 3).end(), 3);
+        assert_eq!(MyRange::new(5, 5).count(), 1);
+        assert_eq!(MyRange::new(isize::MIN, isize::MAX).count(), usize::MAX);
+        assert_eq!(MyRange::new(0u8, 255).count(), 256);
+    }
+
     #[test]
     fn range_set_insert_empty() {
         let mut rs = RangeSet::with_capacity(1);
@@ -261,4 +403,115 @@ mod tests {
         assert_eq!(rs.ranges.len(), 1);
         assert_eq!(rs.ranges[0], MyRange { from: 0, to: 18 });
     }
+
+    fn range_set<T: PrimInt>(ranges: &[(T, T)]) -> RangeSet<T> {
+        let mut rs = RangeSet::with_capacity(ranges.len());
+        for &r in ranges {
+            rs.insert(r.into());
+        }
+        rs
+    }
+
+    #[test]
+    fn range_set_remove() {
+        let mut rs = range_set(&[(0, 10), (20, 30), (40, 50)]);
+
+        rs.remove(MyRange::new(5, 5));
+        assert_eq!(
+            rs.ranges,
+            [
+                (0, 4).into(),
+                (6, 10).into(),
+                (20, 30).into(),
+                (40, 50).into()
+            ]
+        );
+
+        rs.remove(MyRange::new(8, 45));
+        assert_eq!(rs.ranges, [(0, 4).into(), (6, 7).into(), (46, 50).into()]);
+
+        rs.remove(MyRange::new(11, 19));
+        rs.remove((60, 51).into());
+        assert_eq!(rs.count(), 5 + 2 + 5);
+
+        rs.remove(MyRange::new(-100, 100));
+        assert!(rs.is_empty());
+    }
+
+    #[test]
+    fn range_set_union_and_intersection() {
+        let a = range_set(&[(0, 5), (10, 15), (30, 40)]);
+        let b = range_set(&[(3, 12), (14, 20), (41, 41)]);
+
+        assert_eq!(a.union(&b), range_set(&[(0, 20), (30, 41)]));
+        assert_eq!(a.intersection(&b), range_set(&[(3, 5), (10, 12), (14, 15)]));
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+        assert!(a.intersection(&RangeSet::default()).is_empty());
+    }
+
+    #[test]
+    fn range_set_gaps() {
+        let rs = range_set(&[(2, 3), (6, 8), (12, 20)]);
+
+        let gaps: Vec<_> = rs.gaps(MyRange::new(0, 14)).collect();
+        assert_eq!(gaps, [(0, 1).into(), (4, 5).into(), (9, 11).into()]);
+        let gaps: Vec<_> = rs.gaps(MyRange::new(7, 25)).collect();
+        assert_eq!(gaps, [(9, 11).into(), (21, 25).into()]);
+        assert_eq!(rs.gaps(MyRange::new(13, 19)).count(), 0);
+        assert_eq!(
+            rs.complement(MyRange::new(0, 10)),
+            range_set(&[(0, 1), (4, 5), (9, 10)])
+        );
+    }
+
+    #[test]
+    fn range_set_of_unsigned() {
+        let mut rs = range_set(&[(0u32, 0), (u32::MAX, u32::MAX)]);
+        rs.insert(MyRange::new(1, 1));
+
+        assert_eq!(rs.ranges, [(0, 1).into(), (u32::MAX, u32::MAX).into()]);
+        let gaps: Vec<_> = rs.gaps(MyRange::new(0, u32::MAX)).collect();
+        assert_eq!(gaps, [MyRange::new(2, u32::MAX - 1)]);
+        assert!(rs.contains(u32::MAX) && !rs.contains(2));
+    }
+
+    #[test]
+    fn range_set_extremes() {
+        let mut rs = range_set(&[(isize::MAX, isize::MAX), (isize::MIN, isize::MIN)]);
+        assert_eq!(rs.ranges.len(), 2);
+
+        rs.insert((isize::MAX - 1, isize::MIN + 1).into());
+        assert_eq!(rs.ranges, [MyRange::new(isize::MIN, isize::MAX)]);
+
+        rs.remove(MyRange::new(isize::MIN, isize::MIN));
+        rs.remove(MyRange::new(isize::MAX, isize::MAX));
+        assert_eq!(rs.ranges, [MyRange::new(isize::MIN + 1, isize::MAX - 1)]);
+        assert!(!rs.contains(isize::MIN) && rs.contains(isize::MIN + 1));
+    }
+
+    mod model {
+        use proptest::prelude::*;
+
+        use crate::solutions::common::range_set_model::{check, Op, Value};
+
+        fn value() -> impl Strategy<Value = Value> {
+            (any::<u8>(), any::<u8>()).prop_map(|(anchor, offset)| Value { anchor, offset })
+        }
+
+        fn op() -> impl Strategy<Value = Op> {
+            prop_oneof![
+                3 => (value(), value()).prop_map(|(a, b)| Op::Insert(a, b)),
+                1 => (value(), value()).prop_map(|(a, b)| Op::Remove(a, b)),
+            ]
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(2000))]
+
+            #[test]
+            fn range_set_matches_model(ops in prop::collection::vec(op(), 1..40)) {
+                check(&ops);
+            }
+        }
+    }
 }
@@ -0,0 +1,237 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+
+/// A cell of a grid as (row, column), rows go top to bottom.
+pub type Pos = (usize, usize);
+
+/// Steps to the 4 orthogonal neighbours as (row, column) offsets: up, right, down, left.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Steps to all 8 neighbours, clockwise starting from up.
+pub const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Reads a grid with a cell per char of every line. `cell` rejects the chars it doesn't
+    /// expect with a message, which is reported at the char's position.
+    pub fn parse<E: Display>(
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Grid<T>, SolveError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for row in lines(input) {
+            let mut row_width = 0;
+            for (col, c) in row.text.char_indices() {
+                cells.push(cell(c).map_err(|e| row.error(&row.text[col..], e))?);
+                row_width += 1;
+            }
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(row.error(row.end(), "all rows of the map should have the same length"));
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(SolveError::new("the map is empty")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Pos) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, (row, col): Pos) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    /// A grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// All the positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + Clone {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// All the cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The cells of `row`, left to right.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// The cells of `col`, top to bottom.
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(col < self.width, "column {col} is out of the grid");
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    /// The position one `step` away from `pos`, if it's within the grid.
+    pub fn step(&self, (row, col): Pos, (d_row, d_col): (isize, isize)) -> Option<Pos> {
+        let row = row.checked_add_signed(d_row).filter(|&r| r < self.height)?;
+        let col = col.checked_add_signed(d_col).filter(|&c| c < self.width)?;
+
+        Some((row, col))
+    }
+
+    /// The positions reached by repeating `step` from `pos` until the edge of the grid,
+    /// not including `pos` itself.
+    pub fn ray(&self, pos: Pos, step: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.step(pos, step), move |&p| self.step(p, step))
+    }
+
+    /// The orthogonal neighbours of `pos` within the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` within the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ALL_AROUND
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// Draws the grid as lines of text, a char per cell.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+        for row in 0..self.height {
+            result.extend(self.row(row).iter().map(&mut cell));
+            result.push('\n');
+        }
+
+        result
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is out of the {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is out of the {width}x{height} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, SolveError> {
+        Grid::parse(input, |c| c.to_digit(10).ok_or("not a digit"))
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid = digits("123\n456").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).rev().collect::<Vec<_>>(), [&6, &3]);
+        assert_eq!(
+            grid.render(|&d| char::from_digit(d * 2 % 10, 10).unwrap()),
+            "246\n802\n"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = digits("123\n4x6").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(2)));
+        assert_eq!(err.message, "not a digit");
+
+        let err = digits("123\n45").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(3)));
+
+        assert!(digits("").is_err());
+    }
+
+    #[test]
+    fn neighbours_and_rays() {
+        let grid = Grid::new(3, 3, ());
+
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbors8((0, 2)).collect::<Vec<_>>(),
+            [(1, 2), (1, 1), (0, 1)]
+        );
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+
+        assert_eq!(
+            grid.ray((2, 0), (-1, 1)).collect::<Vec<_>>(),
+            [(1, 1), (0, 2)]
+        );
+        assert_eq!(grid.ray((0, 1), (-1, 0)).count(), 0);
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::SolveError;
+
+/// A line of the puzzle input along with its 1-based number, so parse errors can point at it.
+#[derive(Debug, Copy, Clone)]
+pub struct Line<'a> {
+    pub no: usize,
+    pub text: &'a str,
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Line { no: i + 1, text })
+}
+
+impl<'a> Line<'a> {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// 1-based column of `part`, which is expected to be a slice of this line.
+    pub fn column_of(&self, part: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize).wrapping_sub(start);
+
+        if offset <= self.text.len() && self.text.is_char_boundary(offset) {
+            self.text[..offset].chars().count() + 1
+        } else {
+            1
+        }
+    }
+
+    /// The empty slice right past the last char, for "unexpected end of line" kind of errors.
+    pub fn end(&self) -> &'a str {
+        &self.text[self.text.len()..]
+    }
+
+    pub fn error(&self, part: &str, message: impl Display) -> SolveError {
+        SolveError::at(self.no, self.column_of(part), message)
+    }
+
+    pub fn parse<T>(&self, part: &str) -> Result<T, SolveError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        part.parse()
+            .map_err(|e| self.error(part, format_args!("can't parse '{part}': {e}")))
+    }
+
+    /// Strips `prefix` off `rest`, which is expected to be a tail of this line.
+    pub fn strip_prefix(&self, rest: &'a str, prefix: &str) -> Result<&'a str, SolveError> {
+        rest.strip_prefix(prefix)
+            .ok_or_else(|| self.error(rest, format_args!("expected '{}'", prefix.trim())))
+    }
+
+    pub fn split_once(
+        &self,
+        rest: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), SolveError> {
+        rest.split_once(delimiter)
+            .ok_or_else(|| self.error(self.end(), format_args!("expected '{}'", delimiter.trim())))
+    }
+}
+
+/// Splits a line into words and punctuation chars, skipping whitespace, for formats that are
+/// easier to follow one token at a time.
+#[derive(Debug, Clone)]
+pub struct Tokens<'a> {
+    line: Line<'a>,
+    rest: &'a str,
+    // chars that make a token on their own
+    punctuation: &'static str,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(line: Line<'a>, punctuation: &'static str) -> Tokens<'a> {
+        Tokens {
+            line,
+            rest: line.text,
+            punctuation,
+        }
+    }
+
+    pub fn peek(&self) -> Option<&'a str> {
+        self.clone().next()
+    }
+
+    /// Takes the next token, which has to be `expected`.
+    pub fn expect(&mut self, expected: &str) -> Result<&'a str, SolveError> {
+        self.expect_one_of(&[expected])
+    }
+
+    pub fn expect_one_of(&mut self, options: &[&str]) -> Result<&'a str, SolveError> {
+        let token = self.next();
+        if let Some(token) = token.filter(|t| options.contains(t)) {
+            return Ok(token);
+        }
+
+        let options: Vec<String> = options.iter().map(|o| format!("'{o}'")).collect();
+        let expected = match &options[..] {
+            [single] => single.clone(),
+            _ => format!("one of {}", options.join(", ")),
+        };
+        Err(self.unexpected(token, &expected))
+    }
+
+    /// Takes the next token as long as it's a word, `what` describes it in errors.
+    pub fn word(&mut self, what: &str) -> Result<&'a str, SolveError> {
+        match self.next() {
+            Some(token) if !self.is_punctuation(token) => Ok(token),
+            token => Err(self.unexpected(token, what)),
+        }
+    }
+
+    fn is_punctuation(&self, token: &str) -> bool {
+        token.len() == 1 && self.punctuation.contains(token)
+    }
+
+    fn unexpected(&self, token: Option<&'a str>, expected: &str) -> SolveError {
+        match token {
+            Some(token) => self
+                .line
+                .error(token, format_args!("expected {expected}, found '{token}'")),
+            None => self
+                .line
+                .error(self.line.end(), format_args!("expected {expected}")),
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.rest = self.rest.trim_start();
+        let first = self.rest.chars().next()?;
+
+        let len = if self.punctuation.contains(first) {
+            first.len_utf8()
+        } else {
+            self.rest
+                .find(|c: char| c.is_whitespace() || self.punctuation.contains(c))
+                .unwrap_or(self.rest.len())
+        };
+        let (token, rest) = self.rest.split_at(len);
+        self.rest = rest;
+
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns() {
+        let line = Line {
+            no: 3,
+            text: "move 1 from x to 2",
+        };
+        let x = line.text.split(' ').nth(3).unwrap();
+
+        assert_eq!(line.column_of(x), 13);
+        assert_eq!(line.column_of(line.end()), 19);
+
+        let err = line.parse::<usize>(x).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), Some(13)));
+    }
+
+    #[test]
+    fn columns_count_chars() {
+        let line = Line {
+            no: 1,
+            text: "▉▉ x",
+        };
+        assert_eq!(line.column_of(&line.text[7..]), 4);
+    }
+
+    #[test]
+    fn tokens() {
+        let line = Line {
+            no: 2,
+            text: "  Valve AA has  flow rate=-13; tunnels lead to x_1,y-2",
+        };
+        let all: Vec<&str> = Tokens::new(line, "=;,").collect();
+        assert_eq!(
+            all,
+            [
+                "Valve", "AA", "has", "flow", "rate", "=", "-13", ";", "tunnels", "lead", "to",
+                "x_1", ",", "y-2"
+            ]
+        );
+
+        let mut tokens = Tokens::new(line, "=;,");
+        assert_eq!(tokens.expect("Valve").unwrap(), "Valve");
+        assert_eq!(tokens.word("a valve name").unwrap(), "AA");
+        assert_eq!(tokens.peek(), Some("has"));
+
+        let err = tokens.expect_one_of(&["is", "was"]).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(12)));
+        assert_eq!(err.message, "expected one of 'is', 'was', found 'has'");
+
+        // failed expectations still take the token
+        assert_eq!(tokens.next(), Some("flow"));
+        assert_eq!(tokens.word("rate").unwrap(), "rate");
+        let err = tokens.word("a flow rate").unwrap_err();
+        assert_eq!(err.message, "expected a flow rate, found '='");
+
+        let mut tokens = Tokens::new(Line { no: 1, text: "to" }, "");
+        tokens.expect("to").unwrap();
+        let err = tokens.word("a valve name").unwrap_err();
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (Some(3), "expected a valve name")
+        );
+    }
+}
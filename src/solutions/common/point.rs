@@ -0,0 +1,196 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use num_traits::{PrimInt, Signed};
+
+/// A point on a plane, or a vector between two of them. `y` grows downwards, like the rows
+/// of the puzzle inputs do.
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Point2<T = isize> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A difference between two points.
+pub type Vec2<T = isize> = Point2<T>;
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: PrimInt + Signed> Point2<T> {
+    /// The length of the shortest path between the points moving orthogonally, `None` if it
+    /// doesn't fit in `T`.
+    pub fn manhattan_dist(self, other: Self) -> Option<T> {
+        let checked_abs = |v: T| (v != T::min_value()).then(|| v.abs());
+        let dx = checked_abs(other.x.checked_sub(&self.x)?)?;
+        let dy = checked_abs(other.y.checked_sub(&self.y)?)?;
+
+        dx.checked_add(&dy)
+    }
+
+    /// The length of the shortest path between the points moving diagonally too.
+    pub fn chebyshev_dist(self, other: Self) -> T {
+        let d = other - self;
+        d.x.abs().max(d.y.abs())
+    }
+
+    /// A vector with both components clamped to -1, 0 or 1: a single step towards the same
+    /// general direction.
+    pub fn signum(self) -> Self {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// This vector rotated by a right angle clockwise, as seen with `y` growing downwards.
+    pub fn rotate_right(self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    /// This vector rotated by a right angle counterclockwise.
+    pub fn rotate_left(self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Point2::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+/// One of the four orthogonal directions, in clockwise order starting from `Right`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction {
+    Right,
+    Down,
+    Left,
+    Up,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
+
+    /// The unit vector pointing this way.
+    pub fn vec<T: PrimInt + Signed>(self) -> Vec2<T> {
+        let (zero, one) = (T::zero(), T::one());
+        match self {
+            Direction::Right => Vec2::new(one, zero),
+            Direction::Down => Vec2::new(zero, one),
+            Direction::Left => Vec2::new(-one, zero),
+            Direction::Up => Vec2::new(zero, -one),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// Parses one of `L`, `U`, `R` or `D`.
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "L" => Ok(Direction::Left),
+            "U" => Ok(Direction::Up),
+            "R" => Ok(Direction::Right),
+            "D" => Ok(Direction::Down),
+            _ => Err(anyhow!("expected one of L, U, R or D")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let (a, b) = (Point2::new(1, -2), Point2::new(-3, 5));
+
+        assert_eq!(a.manhattan_dist(b), Some(11));
+        assert_eq!(a.chebyshev_dist(b), 7);
+        assert_eq!(b - a, Vec2::new(-4, 7));
+        assert_eq!((b - a).signum(), Vec2::new(-1, 1));
+        assert_eq!(a + (b - a) * 2, Point2::new(-7, 12));
+
+        let far = Point2::new(isize::MIN, 0);
+        assert_eq!(far.manhattan_dist(Point2::new(1, 0)), None);
+    }
+
+    #[test]
+    fn directions() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_right().vec::<i32>(), dir.vec().rotate_right());
+            assert_eq!(dir.turn_left().vec::<i32>(), dir.vec().rotate_left());
+            assert_eq!(dir.reverse().vec::<i32>(), -dir.vec());
+            assert_eq!(dir.turn_left().turn_right(), dir);
+        }
+
+        assert_eq!("U".parse::<Direction>().unwrap().vec(), Vec2::new(0, -1));
+        assert!("X".parse::<Direction>().is_err());
+    }
+}
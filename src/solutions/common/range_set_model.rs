@@ -0,0 +1,102 @@
+//! Checks `RangeSet` against a plain `HashSet` of the values it should contain. Shared by the
+//! property tests and the fuzz target in `fuzz/`.
+
+use std::collections::HashSet;
+
+use crate::solutions::common::RangeSet;
+
+/// Values are picked around a few anchors, including both ends of `isize`, and the model only
+/// tracks the values near them. That keeps it small while ranges can still span billions.
+const ANCHORS: [isize; 5] = [isize::MIN, -1_000, 0, 1_000_000, isize::MAX];
+const SPREAD: u8 = 24;
+
+/// A value near one of the anchors.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(fuzzing, derive(arbitrary::Arbitrary))]
+pub struct Value {
+    pub anchor: u8,
+    pub offset: u8,
+}
+
+impl Value {
+    pub fn get(self) -> isize {
+        let anchor = ANCHORS[self.anchor as usize % ANCHORS.len()];
+        let offset = (self.offset % SPREAD) as isize - (SPREAD / 2) as isize;
+
+        anchor.saturating_add(offset)
+    }
+}
+
+/// Ranges are given by their bounds in either order.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(fuzzing, derive(arbitrary::Arbitrary))]
+pub enum Op {
+    Insert(Value, Value),
+    Remove(Value, Value),
+}
+
+/// Applies `ops` to an empty set, checking that it stays well-formed and agrees with the model
+/// after every step. Panics on the first mismatch.
+pub fn check(ops: &[Op]) {
+    let probes = probes();
+    let mut set = RangeSet::with_capacity(ops.len());
+    let mut model = HashSet::new();
+
+    for (i, &op) in ops.iter().enumerate() {
+        let (Op::Insert(a, b) | Op::Remove(a, b)) = op;
+        let (from, to) = (a.get(), b.get());
+        let in_range = |v: &isize| (from.min(to)..=from.max(to)).contains(v);
+
+        match op {
+            Op::Insert(..) => {
+                set.insert((from, to).into());
+                model.extend(probes.iter().copied().filter(in_range));
+            }
+            Op::Remove(..) => {
+                set.remove((from, to).into());
+                model.retain(|v| !in_range(v));
+            }
+        }
+
+        check_invariants(&set, &ops[..=i]);
+        for v in &probes {
+            assert_eq!(
+                set.contains(*v),
+                model.contains(v),
+                "contains({v}) after {:?}, got {:?}",
+                &ops[..=i],
+                set.ranges()
+            );
+        }
+    }
+}
+
+/// The ranges are sorted, non-empty, and neither overlap nor touch.
+fn check_invariants(set: &RangeSet, ops: &[Op]) {
+    let ranges = set.ranges();
+
+    for r in ranges {
+        assert!(r.from <= r.to, "inverted range {r:?} after {ops:?}");
+    }
+    for pair in ranges.windows(2) {
+        let separated = pair[0]
+            .to
+            .checked_add(1)
+            .is_some_and(|next| next < pair[1].from);
+        assert!(
+            separated,
+            "{:?} and {:?} should be merged after {ops:?}",
+            pair[0], pair[1]
+        );
+    }
+}
+
+fn probes() -> Vec<isize> {
+    let mut probes: Vec<isize> = (0..ANCHORS.len() as u8)
+        .flat_map(|anchor| (0..SPREAD).map(move |offset| Value { anchor, offset }.get()))
+        .collect();
+    probes.sort();
+    probes.dedup();
+
+    probes
+}
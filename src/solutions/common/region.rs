@@ -0,0 +1,317 @@
+use std::cmp::min;
+
+use crate::solutions::common::{MyRange, RangeSet};
+
+/// A shape whose left and right edges move by at most one column per row.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Shape {
+    Rect {
+        xs: MyRange,
+        ys: MyRange,
+    },
+    // all the cells within `radius` from `center` in Manhattan distance
+    Diamond {
+        center: (isize, isize),
+        radius: isize,
+    },
+}
+
+impl Shape {
+    fn rows(&self) -> MyRange {
+        match *self {
+            Shape::Rect { ys, .. } => ys,
+            Shape::Diamond {
+                center: (_, y),
+                radius,
+            } => MyRange::new(y.saturating_sub(radius), y.saturating_add(radius)),
+        }
+    }
+
+    fn row(&self, y: isize) -> Option<MyRange> {
+        if !self.rows().contains(y) {
+            return None;
+        }
+
+        match *self {
+            Shape::Rect { xs, .. } => Some(xs),
+            Shape::Diamond {
+                center: (cx, cy),
+                radius,
+            } => {
+                let half_width = radius.checked_sub_unsigned(y.abs_diff(cy))?;
+                Some(MyRange::new(
+                    cx.saturating_sub(half_width),
+                    cx.saturating_add(half_width),
+                ))
+            }
+        }
+    }
+
+    /// How the edges of row `y` move with every next row: the change of the left and right
+    /// edge, and the number of rows the shape keeps moving that way for.
+    fn slopes(&self, y: isize) -> (isize, isize, isize) {
+        match *self {
+            Shape::Rect { ys, .. } => (0, 0, ys.to - y),
+            Shape::Diamond {
+                center: (_, cy), ..
+            } if y < cy => (-1, 1, cy - y),
+            Shape::Diamond {
+                center: (_, cy),
+                radius,
+            } => (1, -1, cy + radius - y),
+        }
+    }
+
+    fn contains(&self, x: isize, y: isize) -> bool {
+        self.row(y).is_some_and(|xs| xs.contains(x))
+    }
+}
+
+/// A union of shapes on a grid of cells, inspected one row at a time.
+///
+/// Nothing is rasterized, so shapes can be millions of cells across as long as there are not
+/// too many of them.
+#[derive(Debug, Clone, Default)]
+pub struct Region {
+    shapes: Vec<Shape>,
+}
+
+impl Region {
+    pub fn new() -> Region {
+        Region::default()
+    }
+
+    pub fn insert_rect(&mut self, xs: MyRange, ys: MyRange) {
+        self.shapes.push(Shape::Rect { xs, ys });
+    }
+
+    pub fn insert_diamond(&mut self, center: (isize, isize), radius: isize) {
+        if radius >= 0 {
+            self.shapes.push(Shape::Diamond { center, radius });
+        }
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        self.shapes.iter().any(|s| s.contains(x, y))
+    }
+
+    /// The smallest rectangle the region fits in, as its columns and rows.
+    pub fn bounds(&self) -> Option<(MyRange, MyRange)> {
+        let rows = self.shapes.iter().map(|s| s.rows());
+        let cols = self.shapes.iter().map(|s| match *s {
+            Shape::Rect { xs, .. } => xs,
+            Shape::Diamond {
+                center: (x, _),
+                radius,
+            } => MyRange::new(x.saturating_sub(radius), x.saturating_add(radius)),
+        });
+
+        let xs = MyRange::new(
+            cols.clone().map(|r| r.from).min()?,
+            cols.map(|r| r.to).max()?,
+        );
+        let ys = MyRange::new(
+            rows.clone().map(|r| r.from).min()?,
+            rows.map(|r| r.to).max()?,
+        );
+
+        Some((xs, ys))
+    }
+
+    /// The cells of row `y` within the region.
+    pub fn row(&self, y: isize) -> RangeSet {
+        let mut row = RangeSet::with_capacity(self.shapes.len());
+        self.fill_row(&mut row, y);
+
+        row
+    }
+
+    fn fill_row(&self, row: &mut RangeSet, y: isize) {
+        row.clear();
+        for xs in self.shapes.iter().filter_map(|s| s.row(y)) {
+            row.insert(xs);
+        }
+    }
+
+    /// The number of cells in the region. Takes time proportional to its height.
+    pub fn area(&self) -> usize {
+        let Some((_, ys)) = self.bounds() else {
+            return 0;
+        };
+
+        let mut row = RangeSet::with_capacity(self.shapes.len());
+        (ys.from..=ys.to)
+            .map(|y| {
+                self.fill_row(&mut row, y);
+                row.count()
+            })
+            .sum()
+    }
+
+    /// The runs of cells within the `xs` by `ys` rectangle that are outside of the region,
+    /// row by row from the top.
+    ///
+    /// Rows that are fully covered get skipped in bulk, so large regions with few holes are
+    /// cheap to search.
+    pub fn uncovered_runs(
+        &self,
+        xs: MyRange,
+        ys: MyRange,
+    ) -> impl Iterator<Item = (isize, MyRange)> + '_ {
+        let mut next_row = Some(ys.from);
+        let mut row = RangeSet::with_capacity(self.shapes.len());
+        let mut pending = Vec::new().into_iter();
+
+        std::iter::from_fn(move || loop {
+            if let Some(run) = pending.next() {
+                return Some(run);
+            }
+
+            let y = next_row.filter(|&y| y <= ys.to)?;
+            self.fill_row(&mut row, y);
+            let runs: Vec<_> = row.gaps(xs).map(|gap| (y, gap)).collect();
+            let step = if runs.is_empty() {
+                self.covered_rows(y, xs)
+            } else {
+                1
+            };
+
+            next_row = y.checked_add(step);
+            pending = runs.into_iter();
+        })
+    }
+
+    /// The cells within the `xs` by `ys` rectangle that are outside of the region.
+    pub fn uncovered(&self, xs: MyRange, ys: MyRange) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.uncovered_runs(xs, ys)
+            .flat_map(|(y, run)| (run.from..=run.to).map(move |x| (x, y)))
+    }
+
+    /// How many rows starting with `y` are guaranteed to be covered over `xs`, given that
+    /// row `y` is.
+    ///
+    /// Picks a chain of shapes covering the row and follows their edges while they move in a
+    /// straight line: the chain holds as long as every link still reaches the next one.
+    fn covered_rows(&self, y: isize, xs: MyRange) -> isize {
+        let mut candidates: Vec<(MyRange, &Shape)> = self
+            .shapes
+            .iter()
+            .filter_map(|s| s.row(y).map(|r| (r, s)))
+            .collect();
+        candidates.sort_by_key(|(r, _)| r.from);
+
+        // rows after `y` for which the chain still holds
+        let mut rows = isize::MAX;
+        // the last column covered so far, with how fast the edge covering it moves
+        let mut reach: Option<(isize, isize)> = None;
+        let mut next = 0;
+
+        loop {
+            let col = match reach {
+                None => xs.from,
+                Some((to, _)) if to >= xs.to => break,
+                Some((to, _)) => to + 1,
+            };
+
+            let mut best: Option<(MyRange, &Shape)> = None;
+            while let Some(&(r, s)) = candidates.get(next).filter(|(r, _)| r.from <= col) {
+                if best.is_none_or(|(b, _)| r.to > b.to) {
+                    best = Some((r, s));
+                }
+                next += 1;
+            }
+            let Some((r, s)) = best.filter(|(r, _)| r.to >= col) else {
+                // not covered after all
+                return 1;
+            };
+
+            let (left_slope, right_slope, straight) = s.slopes(y);
+            rows = min(rows, straight);
+            // the left edge has to keep up with either the start of `xs` or the previous link
+            let closing = left_slope - reach.map_or(0, |(_, slope)| slope);
+            if closing > 0 {
+                rows = min(rows, (col - r.from) / closing);
+            }
+
+            reach = Some((r.to, right_slope));
+        }
+
+        // the last link has to keep reaching the end of `xs`
+        if let Some((to, slope)) = reach.filter(|&(_, slope)| slope < 0) {
+            rows = min(rows, (to - xs.to) / -slope);
+        }
+
+        rows.saturating_add(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force_uncovered(region: &Region, xs: MyRange, ys: MyRange) -> Vec<(isize, isize)> {
+        let mut cells = Vec::new();
+        for y in ys.from..=ys.to {
+            for x in xs.from..=xs.to {
+                if !region.contains(x, y) {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn shapes() {
+        let mut region = Region::new();
+        region.insert_rect(MyRange::new(0, 2), MyRange::new(0, 1));
+        region.insert_diamond((10, 10), 2);
+
+        assert!(region.contains(2, 1) && !region.contains(3, 1));
+        assert!(region.contains(10, 8) && region.contains(11, 11) && !region.contains(12, 11));
+        assert_eq!(region.area(), 6 + 13);
+        assert_eq!(
+            region.bounds(),
+            Some((MyRange::new(0, 12), MyRange::new(0, 12)))
+        );
+        assert_eq!(region.row(10).ranges(), [MyRange::new(8, 12)]);
+
+        // only the last cell sticks out of the diamond
+        region.insert_rect(MyRange::new(10, 13), MyRange::new(10, 10));
+        assert_eq!(region.area(), 6 + 13 + 1);
+    }
+
+    #[test]
+    fn uncovered_matches_brute_force() {
+        let mut region = Region::new();
+        region.insert_diamond((5, 5), 4);
+        region.insert_diamond((14, 5), 4);
+        region.insert_diamond((9, 14), 5);
+        region.insert_diamond((20, 20), 6);
+        region.insert_rect(MyRange::new(-3, 2), MyRange::new(10, 25));
+        region.insert_rect(MyRange::new(10, 30), MyRange::new(-2, 0));
+
+        for (xs, ys) in [
+            (MyRange::new(0, 20), MyRange::new(0, 20)),
+            (MyRange::new(-5, 30), MyRange::new(-5, 30)),
+            (MyRange::new(4, 6), MyRange::new(3, 7)),
+        ] {
+            let cells: Vec<_> = region.uncovered(xs, ys).collect();
+            assert_eq!(cells, brute_force_uncovered(&region, xs, ys));
+        }
+    }
+
+    #[test]
+    fn skips_covered_rows() {
+        // every pair of diamonds leaves a diagonal uncovered, the diagonals cross in the middle
+        let mut region = Region::new();
+        region.insert_diamond((0, 0), 999_999);
+        region.insert_diamond((1_000_000, 1_000_000), 999_999);
+        region.insert_diamond((0, 1_000_000), 999_999);
+        region.insert_diamond((1_000_000, 0), 999_999);
+
+        let xs = MyRange::new(0, 1_000_000);
+        let cells: Vec<_> = region.uncovered(xs, xs).take(3).collect();
+        assert_eq!(cells, [(500_000, 500_000)]);
+    }
+}
@@ -0,0 +1,278 @@
+//! Shortest paths over graphs given by a neighbour function, so that grids and the like don't
+//! have to be turned into adjacency lists first.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use ahash::AHashMap;
+use num_traits::Zero;
+
+/// The nodes a search has reached, with their distance from the closest start and the node
+/// they were reached from.
+#[derive(Debug, Clone)]
+pub struct SearchTree<N, C> {
+    nodes: AHashMap<N, (C, Option<N>)>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> SearchTree<N, C> {
+    /// The distance from the closest start to `node`, if the search reached it.
+    pub fn dist(&self, node: &N) -> Option<C> {
+        self.nodes.get(node).map(|&(dist, _)| dist)
+    }
+
+    /// The reached nodes with their distances, in no particular order.
+    pub fn reached(&self) -> impl Iterator<Item = (&N, C)> {
+        self.nodes.iter().map(|(node, &(dist, _))| (node, dist))
+    }
+
+    /// The shortest path found from one of the starts to `node`, both ends included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut path = vec![node.clone()];
+        let mut prev = &self.nodes.get(node)?.1;
+        while let Some(node) = prev {
+            path.push(node.clone());
+            prev = &self.nodes[node].1;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Breadth-first search from all of `starts` at once, every edge being 1 long. Stops as soon as
+/// a node satisfying `goal` is reached, or once every reachable node is.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> SearchTree<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut nodes = AHashMap::new();
+    let mut q = VecDeque::new();
+
+    for start in starts {
+        if nodes.contains_key(&start) {
+            continue;
+        }
+        nodes.insert(start.clone(), (0, None));
+        if goal(&start) {
+            return SearchTree { nodes };
+        }
+        q.push_back((0, start));
+    }
+
+    while let Some((d, node)) = q.pop_front() {
+        for next in neighbours(&node) {
+            if nodes.contains_key(&next) {
+                continue;
+            }
+            nodes.insert(next.clone(), (d + 1, Some(node.clone())));
+            if goal(&next) {
+                return SearchTree { nodes };
+            }
+            q.push_back((d + 1, next));
+        }
+    }
+
+    SearchTree { nodes }
+}
+
+/// Dijkstra's search from all of `starts` at once over edges of non-negative length. Stops once
+/// the distance to a node satisfying `goal` is known, or once every reachable node is done.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> SearchTree<N, C>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut nodes: AHashMap<N, (C, Option<N>)> = AHashMap::new();
+    let mut q = BinaryHeap::new();
+
+    for start in starts {
+        nodes.insert(start.clone(), (C::zero(), None));
+        q.push(Reverse((C::zero(), start)));
+    }
+
+    while let Some(Reverse((d, node))) = q.pop() {
+        if nodes[&node].0 < d {
+            // a shorter way there has been handled already
+            continue;
+        }
+        if goal(&node) {
+            break;
+        }
+
+        for (next, len) in neighbours(&node) {
+            let next_d = d + len;
+            if nodes.get(&next).is_some_and(|&(known, _)| known <= next_d) {
+                continue;
+            }
+            nodes.insert(next.clone(), (next_d, Some(node.clone())));
+            q.push(Reverse((next_d, next)));
+        }
+    }
+
+    SearchTree { nodes }
+}
+
+/// A* search from `start` to the closest node satisfying `goal`, returning its distance and
+/// the path there. `heuristic` must never overestimate the distance left to a goal.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash + Ord,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut nodes: AHashMap<N, (C, Option<N>)> = AHashMap::new();
+    let mut q = BinaryHeap::new();
+
+    nodes.insert(start.clone(), (C::zero(), None));
+    q.push(Reverse((heuristic(&start), C::zero(), start)));
+
+    while let Some(Reverse((_, d, node))) = q.pop() {
+        if nodes[&node].0 < d {
+            continue;
+        }
+        if goal(&node) {
+            let tree = SearchTree { nodes };
+            return Some((d, tree.path_to(&node)?));
+        }
+
+        for (next, len) in neighbours(&node) {
+            let next_d = d + len;
+            if nodes.get(&next).is_some_and(|&(known, _)| known <= next_d) {
+                continue;
+            }
+            nodes.insert(next.clone(), (next_d, Some(node.clone())));
+            q.push(Reverse((next_d + heuristic(&next), next_d, next)));
+        }
+    }
+
+    None
+}
+
+/// Shortest paths between every pair of nodes `0..n`.
+#[derive(Debug, Clone)]
+pub struct AllPairs<C> {
+    n: usize,
+    // the distance, and the node to go to next along the shortest path
+    paths: Vec<Option<(C, usize)>>,
+}
+
+impl<C: Copy + Ord + Zero + Add<Output = C>> AllPairs<C> {
+    /// Floyd–Warshall over the directed `edges`, given as (from, to, length).
+    pub fn new(n: usize, edges: impl IntoIterator<Item = (usize, usize, C)>) -> AllPairs<C> {
+        let mut paths = vec![None; n * n];
+        for i in 0..n {
+            paths[i * n + i] = Some((C::zero(), i));
+        }
+        for (from, to, len) in edges {
+            let path = &mut paths[from * n + to];
+            if path.is_none_or(|(known, _)| len < known) {
+                *path = Some((len, to));
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some((to_k, next)) = paths[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some((from_k, _)) = paths[k * n + j] else {
+                        continue;
+                    };
+                    let through_k = to_k + from_k;
+                    if paths[i * n + j].is_none_or(|(known, _)| through_k < known) {
+                        paths[i * n + j] = Some((through_k, next));
+                    }
+                }
+            }
+        }
+
+        AllPairs { n, paths }
+    }
+
+    pub fn dist(&self, from: usize, to: usize) -> Option<C> {
+        self.paths[from * self.n + to].map(|(dist, _)| dist)
+    }
+
+    /// The nodes along a shortest path, both ends included.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut path = vec![from];
+        let mut node = from;
+        while node != to {
+            node = self.paths[node * self.n + to]?.1;
+            path.push(node);
+        }
+
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -1- 1 -1- 2
+    //  \         /
+    //   ----5----
+    fn weighted(node: &usize) -> Vec<(usize, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 5)],
+            1 => vec![(0, 1), (2, 1)],
+            2 => vec![(1, 1), (0, 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_on_a_line() {
+        let next = |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..10).contains(n));
+
+        let tree = bfs([3], next, |&n| n == 7);
+        assert_eq!(tree.dist(&7), Some(4));
+        assert_eq!(tree.path_to(&7), Some(vec![3, 4, 5, 6, 7]));
+        assert_eq!(tree.dist(&9), None);
+
+        let tree = bfs([0, 9], next, |_| false);
+        assert_eq!(tree.reached().count(), 10);
+        assert_eq!(tree.dist(&5), Some(4));
+        assert_eq!(tree.path_to(&1), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn weighted_searches() {
+        let tree = dijkstra([0], weighted, |_| false);
+        assert_eq!(tree.dist(&2), Some(2));
+        assert_eq!(tree.path_to(&2), Some(vec![0, 1, 2]));
+
+        assert_eq!(
+            astar(0, weighted, |&n| 2 - n as u32, |&n| n == 2),
+            Some((2, vec![0, 1, 2]))
+        );
+        assert_eq!(astar(0, weighted, |_| 0, |&n| n == 3), None);
+
+        let edges = (0..3).flat_map(|n| weighted(&n).into_iter().map(move |(m, len)| (n, m, len)));
+        let all = AllPairs::new(4, edges);
+        assert_eq!(all.dist(2, 0), Some(2));
+        assert_eq!(all.path(2, 0), Some(vec![2, 1, 0]));
+        assert_eq!(all.dist(3, 3), Some(0));
+        assert_eq!(all.dist(0, 3), None);
+        assert_eq!(all.path(0, 3), None);
+    }
+}
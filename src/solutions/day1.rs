@@ -1,28 +1,72 @@
-use crate::Stage;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::Solution;
 
-pub fn solve(stage: Stage, input: &str) -> String {
+pub struct Solver;
+
+impl Solution for Solver {
+    // calories carried by each elf, most first
+    type Parsed<'a> = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, SolveError> {
+        read_sums(input)
+    }
+
+    fn part1(sums: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(sums.iter().take(1).sum::<i64>().into())
+    }
+
+    fn part2(sums: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(sums.iter().take(3).sum::<i64>().into())
+    }
+}
+
+fn read_sums(input: &str) -> Result<Vec<i64>, SolveError> {
     let mut sums = Vec::<i64>::new();
     let mut current_sum = 0i64;
 
-    for row in input.lines() {
-        if row.len() == 0 {
+    for row in lines(input) {
+        if row.is_empty() {
             sums.push(current_sum);
             current_sum = 0;
             continue;
         }
-        current_sum += row.parse::<i64>().unwrap();
+        current_sum += row.parse::<i64>(row.text)?;
     }
     sums.push(current_sum);
 
     sums.sort_by_key(|x| -(*x));
 
-    let result: i64 = sums
-        .iter()
-        .take(match stage {
-            Stage::Easy => 1,
-            Stage::Hard => 3,
-        })
-        .sum();
+    Ok(sums)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
 
-    result.to_string()
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn example() {
+        let sums = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(sums, [24000, 11000, 10000, 6000, 4000]);
+        assert_eq!(Solver::part1(&sums).unwrap(), 24000.into());
+        assert_eq!(Solver::part2(&sums).unwrap(), 45000.into());
+    }
 }
@@ -3,46 +3,64 @@ use std::str::FromStr;
 
 use anyhow::anyhow;
 
-use crate::Stage;
+use crate::answer::{Answer, PixelGrid};
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::Solution;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    match stage {
-        Stage::Easy => solve_easy(input),
-        Stage::Hard => solve_hard(input),
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Vec<Instruction>, SolveError> {
+        lines(input).map(|line| line.parse(line.text)).collect()
+    }
+
+    fn part1(program: &Vec<Instruction>) -> Result<Answer, SolveError> {
+        Ok(solve_easy(program).into())
+    }
+
+    fn part2(program: &Vec<Instruction>) -> Result<Answer, SolveError> {
+        Ok(solve_hard(program).into())
     }
 }
 
-fn solve_hard(input: &str) -> String {
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+fn solve_hard(program: &[Instruction]) -> PixelGrid {
     let mut vm = Vm::new();
-    let mut screen = String::new();
-
-    for (state, i_pixel) in vm.execute_program(input).zip(0..) {
-        let coord = i_pixel % 40;
-        screen.push(if (state.x - coord).abs() < 2 {
-            '▉'
-        } else {
-            ' '
-        });
-        if coord == 39 {
-            screen.push('\n')
-        }
+    let mut screen = PixelGrid::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+
+    for (state, i_pixel) in vm
+        .execute_program(program)
+        .take(SCREEN_WIDTH * SCREEN_HEIGHT)
+        .zip(0..)
+    {
+        let coord = i_pixel % SCREEN_WIDTH;
+        screen.set(
+            coord,
+            i_pixel / SCREEN_WIDTH,
+            (state.x - coord as i64).abs() < 2,
+        );
     }
 
     screen
 }
 
-fn solve_easy(input: &str) -> String {
+fn solve_easy(program: &[Instruction]) -> i64 {
     let mut vm = Vm::new();
 
     let total_strength: i64 = vm
-        .execute_program(input)
+        .execute_program(program)
         .enumerate()
         .skip(19)
         .step_by(40)
         .map(|(i, st)| ((i + 1) as i64) * st.x)
         .sum();
 
-    total_strength.to_string()
+    total_strength
 }
 
 #[derive(Copy, Clone)]
@@ -68,13 +86,13 @@ impl Vm {
         }
     }
 
-    fn execute_program(&mut self, program: &str) -> VmExecutionIterator {
+    fn execute_program(&mut self, program: &[Instruction]) -> VmExecutionIterator<'_> {
         VmExecutionIterator::new(self, program)
     }
 }
 
 #[derive(Copy, Clone)]
-enum Instruction {
+pub enum Instruction {
     Noop,
     Addx(i64),
 }
@@ -113,9 +131,9 @@ struct VmExecutionIterator<'a> {
 }
 
 impl<'a> VmExecutionIterator<'a> {
-    fn new(vm: &'a mut Vm, program: &str) -> VmExecutionIterator<'a> {
+    fn new(vm: &'a mut Vm, program: &[Instruction]) -> VmExecutionIterator<'a> {
         let program: Vec<Instruction> = iter::once(Instruction::Noop)
-            .chain(program.lines().map(|x| x.parse().unwrap()))
+            .chain(program.iter().copied())
             .collect();
 
         let delay = program[0].get_delay();
@@ -154,3 +172,196 @@ impl<'a> Iterator for VmExecutionIterator<'a> {
         Some(self.vm.state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_EXAMPLE: &str = "noop\naddx 3\naddx -5";
+
+    const EXAMPLE: &str = "\
+addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop";
+
+    const EXAMPLE_SCREEN: &str = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+
+    // the value of X during each cycle, starting from the first one
+    fn x_during_cycles(program: &[Instruction]) -> Vec<i64> {
+        Vm::new().execute_program(program).map(|st| st.x).collect()
+    }
+
+    #[test]
+    fn small_example() {
+        let program = Solver::parse(SMALL_EXAMPLE).unwrap();
+
+        assert_eq!(x_during_cycles(&program)[..5], [1, 1, 1, 4, 4]);
+    }
+
+    #[test]
+    fn example() {
+        let program = Solver::parse(EXAMPLE).unwrap();
+
+        let x = x_during_cycles(&program);
+        let strengths: Vec<i64> = [20, 60, 100, 140, 180, 220]
+            .into_iter()
+            .map(|cycle| cycle * x[cycle as usize - 1])
+            .collect();
+        assert_eq!(strengths, [420, 1140, 1800, 2940, 2880, 3960]);
+
+        assert_eq!(Solver::part1(&program).unwrap(), 13140.into());
+        assert_eq!(
+            Solver::part2(&program).unwrap(),
+            PixelGrid::from_art(EXAMPLE_SCREEN).into()
+        );
+    }
+}
@@ -1,40 +1,68 @@
-use crate::Stage;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::Solution;
 use anyhow::anyhow;
 use std::str::FromStr;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let mut monkeys: Vec<_> = input
-        .lines()
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Vec<Monkey>, SolveError> {
+        read_monkeys(input)
+    }
+
+    fn part1(monkeys: &Vec<Monkey>) -> Result<Answer, SolveError> {
+        Ok(monkey_business(monkeys.clone(), 20, 3, u64::MAX).into())
+    }
+
+    fn part2(monkeys: &Vec<Monkey>) -> Result<Answer, SolveError> {
+        let modulus = monkeys.iter().map(|m| m.divisibility_test).product();
+        Ok(monkey_business(monkeys.clone(), 10_000, 1, modulus).into())
+    }
+}
+
+fn read_monkeys(input: &str) -> Result<Vec<Monkey>, SolveError> {
+    let monkeys_src: Vec<_> = lines(input)
         .collect::<Vec<_>>()
         .chunks(7)
-        .map(Monkey::from_description)
+        .map(Vec::from)
         .collect();
+    let mut monkeys = Vec::with_capacity(monkeys_src.len());
+    for (i, descr) in monkeys_src.iter().enumerate() {
+        monkeys.push(Monkey::from_description(i, monkeys_src.len(), descr)?);
+    }
+    if monkeys.len() < 2 {
+        return Err(SolveError::new("at least two monkeys are expected"));
+    }
 
-    let (iterations, relaxing_factor, modulus) = match stage {
-        Stage::Easy => (20, 3, u64::MAX),
-        Stage::Hard => (
-            10_000,
-            1,
-            monkeys.iter().map(|m| m.divisibility_test).product(),
-        ),
-    };
+    Ok(monkeys)
+}
 
+fn monkey_business(
+    mut monkeys: Vec<Monkey>,
+    iterations: usize,
+    relaxing_factor: u64,
+    modulus: u64,
+) -> usize {
     for _ in 0..iterations {
         simulate_round(&mut monkeys, relaxing_factor, modulus);
     }
 
     let busiest = max2(monkeys.iter().map(|m| m.inspected_cnt));
 
-    (busiest.0 * busiest.1).to_string()
+    busiest.0 * busiest.1
 }
 
-fn simulate_round(monkeys: &mut Vec<Monkey>, relaxing_factor: u64, modulus: u64) {
+fn simulate_round(monkeys: &mut [Monkey], relaxing_factor: u64, modulus: u64) {
     for i in 0..monkeys.len() {
         let (monkey, (target_false, target_true)) = extract_monkeys(monkeys, i);
         while let Some(item) = monkey.items.pop() {
             monkey.inspected_cnt += 1;
             let new_value = (monkey.transform.apply(item) / relaxing_factor) % modulus;
-            let test_result = new_value % monkey.divisibility_test == 0;
+            let test_result = new_value.is_multiple_of(monkey.divisibility_test);
 
             let target = if test_result {
                 &mut *target_true
@@ -46,10 +74,7 @@ fn simulate_round(monkeys: &mut Vec<Monkey>, relaxing_factor: u64, modulus: u64)
     }
 }
 
-fn extract_monkeys(
-    monkeys: &mut Vec<Monkey>,
-    i: usize,
-) -> (&mut Monkey, (&mut Monkey, &mut Monkey)) {
+fn extract_monkeys(monkeys: &mut [Monkey], i: usize) -> (&mut Monkey, (&mut Monkey, &mut Monkey)) {
     let (left, right) = monkeys.split_at_mut(i);
     let (m, right) = right.split_first_mut().unwrap();
     let target_idx_left = &m.target_idx;
@@ -110,7 +135,8 @@ fn max2<T: Ord>(mut iter: impl Iterator<Item = T>) -> (T, T) {
     (a, b)
 }
 
-struct Monkey {
+#[derive(Clone)]
+pub struct Monkey {
     items: Vec<u64>,
     transform: Transform,
     divisibility_test: u64,
@@ -119,6 +145,7 @@ struct Monkey {
     inspected_cnt: usize,
 }
 
+#[derive(Clone)]
 struct Transform {
     op: Operation,
     arg1: Operand,
@@ -136,11 +163,13 @@ impl Transform {
     }
 }
 
+#[derive(Clone)]
 enum Operation {
     Add,
     Mul,
 }
 
+#[derive(Clone)]
 enum Operand {
     Current,
     Imm(u64),
@@ -156,24 +185,47 @@ impl Operand {
 }
 
 impl Monkey {
-    fn from_description(descr: &[&str]) -> Monkey {
-        assert!(descr.len() >= 6);
-
-        Monkey {
-            items: descr[1]
-                .split_at(18)
-                .1
-                .split(", ")
-                .map(|x| x.parse().unwrap())
-                .collect(),
-            transform: descr[2].split_at(19).1.parse().unwrap(),
-            divisibility_test: descr[3].split_at(21).1.parse().unwrap(),
-            target_idx: [
-                descr[5].split_at(30).1.parse().unwrap(),
-                descr[4].split_at(29).1.parse().unwrap(),
-            ],
-            inspected_cnt: 0,
+    fn from_description(idx: usize, count: usize, descr: &[Line]) -> Result<Monkey, SolveError> {
+        if descr.len() < 6 {
+            let last = descr.last().unwrap();
+            return Err(last.error(last.end(), "incomplete monkey description"));
+        }
+
+        let field = |i: usize, prefix: &str| descr[i].strip_prefix(descr[i].text, prefix);
+        let target = |i: usize, prefix: &str| -> Result<usize, SolveError> {
+            let target = field(i, prefix)?;
+            match descr[i].parse(target)? {
+                t if t == idx => Err(descr[i].error(target, "a monkey can't throw to itself")),
+                t if t >= count => Err(descr[i].error(target, "no such monkey")),
+                t => Ok(t),
+            }
+        };
+
+        let items = field(1, "  Starting items: ")?;
+        let if_true = target(4, "    If true: throw to monkey ")?;
+        let if_false = target(5, "    If false: throw to monkey ")?;
+        if if_true == if_false {
+            let target = field(5, "    If false: throw to monkey ")?;
+            return Err(descr[5].error(target, "the monkey throws to the same monkey either way"));
         }
+
+        Ok(Monkey {
+            items: if items.is_empty() {
+                Vec::new()
+            } else {
+                items
+                    .split(", ")
+                    .map(|x| descr[1].parse(x))
+                    .collect::<Result<_, _>>()?
+            },
+            transform: descr[2].parse(field(2, "  Operation: new = ")?)?,
+            divisibility_test: match descr[3].parse(field(3, "  Test: divisible by ")?)? {
+                0 => return Err(descr[3].error(descr[3].text, "can't test divisibility by 0")),
+                v => v,
+            },
+            target_idx: [if_false, if_true],
+            inspected_cnt: 0,
+        })
     }
 }
 
@@ -205,11 +257,95 @@ impl FromStr for Transform {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split(' ');
+        let mut next = || parts.next().ok_or(anyhow!("expected 'arg1 op arg2'"));
 
         Ok(Transform {
-            arg1: parts.next().unwrap().parse()?,
-            op: parts.next().unwrap().parse()?,
-            arg2: parts.next().unwrap().parse()?,
+            arg1: next()?.parse()?,
+            op: next()?.parse()?,
+            arg2: next()?.parse()?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    fn inspected_counts(monkeys: &[Monkey]) -> Vec<usize> {
+        monkeys.iter().map(|m| m.inspected_cnt).collect()
+    }
+
+    #[test]
+    fn example() {
+        let monkeys = Solver::parse(EXAMPLE).unwrap();
+
+        let mut relaxed = monkeys.clone();
+        simulate_round(&mut relaxed, 3, u64::MAX);
+        // monkeys throw items in whatever order, only the contents of their piles matter
+        let mut items: Vec<Vec<u64>> = relaxed.iter().map(|m| m.items.clone()).collect();
+        items.iter_mut().for_each(|i| i.sort());
+        assert_eq!(
+            items,
+            [
+                vec![20, 23, 26, 27],
+                vec![25, 167, 207, 401, 1046, 2080],
+                vec![],
+                vec![]
+            ]
+        );
+        for _ in 1..20 {
+            simulate_round(&mut relaxed, 3, u64::MAX);
+        }
+        assert_eq!(inspected_counts(&relaxed), [101, 95, 7, 105]);
+
+        let mut worried = monkeys.clone();
+        let modulus = monkeys.iter().map(|m| m.divisibility_test).product();
+        for _ in 0..10_000 {
+            simulate_round(&mut worried, 1, modulus);
+        }
+        assert_eq!(inspected_counts(&worried), [52166, 47830, 1938, 52013]);
+
+        assert_eq!(Solver::part1(&monkeys).unwrap(), 10605.into());
+        assert_eq!(Solver::part2(&monkeys).unwrap(), 2713310158u64.into());
+    }
+
+    #[test]
+    fn same_target_either_way() {
+        let input = EXAMPLE.replace("If false: throw to monkey 0", "If false: throw to monkey 2");
+        let err = Solver::parse(&input).err().unwrap();
+        assert_eq!((err.line, err.column), (Some(13), Some(31)));
+        assert_eq!(
+            err.message,
+            "the monkey throws to the same monkey either way"
+        );
+    }
+}
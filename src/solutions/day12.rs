@@ -1,117 +1,289 @@
-use std::cmp::min;
-use std::collections::VecDeque;
-use std::iter;
+use std::cmp::Ordering;
+use std::sync::OnceLock;
 
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::grid::{Grid, Pos};
+use crate::solutions::common::search::{self, SearchTree};
+use crate::solutions::Solution;
 use crate::Stage;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let map: Vec<_> = input.lines().collect();
-    let (full, nearest) = bfs(&map);
+pub struct Solver;
 
-    (match stage {
-        Stage::Easy => full,
-        Stage::Hard => nearest,
-    })
-    .to_string()
+impl Solution for Solver {
+    type Parsed<'a> = HeightMap;
+
+    fn parse(input: &str) -> Result<HeightMap, SolveError> {
+        HeightMap::parse(input)
+    }
+
+    fn part1(map: &HeightMap) -> Result<Answer, SolveError> {
+        Ok(steps(&route_from_start(map)?).into())
+    }
+
+    fn part2(map: &HeightMap) -> Result<Answer, SolveError> {
+        Ok(steps(&route_from_lowest(map)?).into())
+    }
+
+    fn explain(map: &HeightMap, stage: Stage) -> Result<Option<String>, SolveError> {
+        let route = match stage {
+            Stage::Easy => route_from_start(map)?,
+            Stage::Hard => route_from_lowest(map)?,
+        };
+
+        Ok(Some(render_route(map, &route)))
+    }
 }
 
-fn bfs(map: &[&str]) -> (usize, usize) {
-    let mut dist_map: Vec<Vec<Option<usize>>> = map
-        .iter()
-        .map(|row| iter::repeat(None).take(row.len()).collect())
-        .collect();
-    let (start, end) = extract_endpoints(map);
-    let mut q = VecDeque::with_capacity(16);
-    q.push_back((0usize, start));
-
-    while let Some((d, (i, j))) = q.pop_front() {
-        let dist = &mut dist_map[i][j];
-        if dist.is_some() {
-            continue;
-        }
-        *dist = Some(d);
+fn steps(route: &[Pos]) -> usize {
+    route.len() - 1
+}
+
+/// The shortest route from `S` to `E`, as the cells along it from `S` to `E` itself.
+fn route_from_start(map: &HeightMap) -> Result<Vec<Pos>, SolveError> {
+    map.routes_to_end()
+        .route_from(map.start)
+        .ok_or(SolveError::new("the best signal location is unreachable"))
+}
+
+/// The shortest route to `E` from the nearest cell of elevation 'a', `S` included.
+fn route_from_lowest(map: &HeightMap) -> Result<Vec<Pos>, SolveError> {
+    map.routes_to_end()
+        .route_from_height(map, b'a')
+        .ok_or(SolveError::new(
+            "no cell of elevation 'a' can reach the best signal location",
+        ))
+}
+
+/// The map as given, `S` and `E` included, along with where those two are.
+#[derive(Debug)]
+pub struct HeightMap {
+    cells: Grid<u8>,
+    start: Pos,
+    end: Pos,
+    // searched once for both stages, on first use so that parsing stays cheap
+    to_end: OnceLock<RoutesTo>,
+}
+
+impl HeightMap {
+    fn parse(input: &str) -> Result<HeightMap, SolveError> {
+        let cells = Grid::parse(input, |c| match c {
+            'a'..='z' | 'S' | 'E' => Ok(c as u8),
+            _ => Err(format!("unexpected height '{c}'")),
+        })?;
 
-        let height = map[i].as_bytes()[j];
+        Ok(HeightMap {
+            start: find_marker(&cells, b'S')?,
+            end: find_marker(&cells, b'E')?,
+            cells,
+            to_end: OnceLock::new(),
+        })
+    }
 
-        if end == (i, j) {
-            break;
+    /// The elevation of a cell, from 'a' to 'z'.
+    fn height(&self, pos: Pos) -> u8 {
+        match self.cells[pos] {
+            b'S' => b'a',
+            b'E' => b'z',
+            h => h,
         }
+    }
 
-        let height = fix_height(height);
-
-        let potential_neighbors = [
-            (i as isize - 1, j as isize),
-            (i as isize + 1, j as isize),
-            (i as isize, j as isize - 1),
-            (i as isize, j as isize + 1),
-        ];
-
-        for (n_i, n_j) in potential_neighbors {
-            if n_i < 0
-                || n_j < 0
-                || n_i >= dist_map.len() as isize
-                || n_j >= dist_map[0].len() as isize
-            {
-                continue;
-            }
+    fn can_climb(&self, from: Pos, to: Pos) -> bool {
+        self.height(to) <= self.height(from) + 1
+    }
 
-            let (n_i, n_j) = (n_i as usize, n_j as usize);
+    /// The shortest routes from everywhere to the closest of `targets`, found by searching
+    /// backwards from all of them at once.
+    fn routes_to(&self, targets: impl IntoIterator<Item = Pos>) -> RoutesTo {
+        let tree = search::bfs(
+            targets,
+            |&pos| {
+                self.cells
+                    .neighbors4(pos)
+                    .filter(move |&prev| self.can_climb(prev, pos))
+            },
+            |_| false,
+        );
 
-            let n_dist = &dist_map[n_i][n_j];
-            if n_dist.is_some() {
-                continue;
-            }
+        RoutesTo { tree }
+    }
 
-            let n_height = fix_height(map[n_i].as_bytes()[n_j]);
-            if height - n_height > 1 {
-                continue;
-            }
+    fn routes_to_end(&self) -> &RoutesTo {
+        self.to_end.get_or_init(|| self.routes_to([self.end]))
+    }
+}
 
-            q.push_back((d + 1, (n_i, n_j)));
-        }
+fn find_marker(cells: &Grid<u8>, marker: u8) -> Result<Pos, SolveError> {
+    let mut found = cells
+        .iter()
+        .filter(|&(_, &c)| c == marker)
+        .map(|(pos, _)| pos);
+    let first = found.next().ok_or_else(|| {
+        SolveError::new(format_args!("the map has no '{}' marker", marker as char))
+    })?;
+
+    match found.next() {
+        // lines and columns are 1-based, and every cell takes a single char
+        Some((row, col)) => Err(SolveError::at(
+            row + 1,
+            col + 1,
+            format_args!("the map has more than one '{}' marker", marker as char),
+        )),
+        None => Ok(first),
     }
+}
 
-    let result_dist = dist_map[end.0][end.1].unwrap();
-    let lowest_a = {
-        let mut result = usize::MAX;
-        for (row_height, row_dist) in map.iter().zip(dist_map) {
-            for (height, dist) in row_height.as_bytes().iter().zip(row_dist) {
-                if *height != b'a' {
-                    continue;
-                }
-                if let Some(dist) = dist {
-                    result = min(result, dist)
-                }
-            }
-        }
-        result
-    };
+/// The result of a backwards search: each cell knows its next step towards the targets.
+#[derive(Debug)]
+struct RoutesTo {
+    tree: SearchTree<Pos, usize>,
+}
+
+impl RoutesTo {
+    /// The shortest route from `pos` up to one of the targets.
+    fn route_from(&self, pos: Pos) -> Option<Vec<Pos>> {
+        let mut route = self.tree.path_to(&pos)?;
+        route.reverse();
 
-    (result_dist, lowest_a)
+        Some(route)
+    }
+
+    /// The shortest route from any cell of elevation `height`.
+    fn route_from_height(&self, map: &HeightMap, height: u8) -> Option<Vec<Pos>> {
+        self.tree
+            .reached()
+            .filter(|&(&pos, _)| map.height(pos) == height)
+            .min_by_key(|&(&pos, dist)| (dist, pos))
+            .and_then(|(&pos, _)| self.route_from(pos))
+    }
 }
 
-fn fix_height(h: u8) -> i8 {
-    (match h {
-        b'S' => b'a',
-        b'E' => b'z',
-        x => x,
-    }) as i8
+/// Draws `route` the way the puzzle does: every cell along it points to the next one.
+fn render_route(map: &HeightMap, route: &[Pos]) -> String {
+    let mut picture = map.cells.map(|_| '.');
+    for pair in route.windows(2) {
+        let [(r, c), (next_r, next_c)] = [pair[0], pair[1]];
+        picture[(r, c)] = match (next_r.cmp(&r), next_c.cmp(&c)) {
+            (Ordering::Less, _) => '^',
+            (Ordering::Greater, _) => 'v',
+            (_, Ordering::Less) => '<',
+            _ => '>',
+        };
+    }
+    if let Some(&last) = route.last() {
+        picture[last] = map.cells[last] as char;
+    }
+
+    picture.render(|&c| c)
 }
 
-fn extract_endpoints(map: &[&str]) -> ((usize, usize), (usize, usize)) {
-    let mut start: Option<(usize, usize)> = None;
-    let mut end: Option<(usize, usize)> = None;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for (i, row) in map.iter().enumerate() {
-        for (j, c) in row.as_bytes().iter().enumerate() {
-            if *c == b'E' {
-                start = Some((i, j));
-            }
-            if *c == b'S' {
-                end = Some((i, j));
+    const EXAMPLE: &str = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+    #[test]
+    fn example() {
+        let map = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Solver::part1(&map).unwrap(), 31.into());
+        assert_eq!(Solver::part2(&map).unwrap(), 29.into());
+        assert_eq!(route_from_start(&map).unwrap()[0], (0, 0));
+        assert_eq!(route_from_lowest(&map).unwrap()[0], (4, 0));
+    }
+
+    #[test]
+    fn stages_share_the_search() {
+        let map = Solver::parse(EXAMPLE).unwrap();
+        assert!(map.to_end.get().is_none());
+
+        Solver::part1(&map).unwrap();
+        let searched: *const RoutesTo = map.routes_to_end();
+        Solver::part2(&map).unwrap();
+        assert!(std::ptr::eq(searched, map.routes_to_end()));
+    }
+
+    #[test]
+    fn routes_climb_one_step_at_a_time() {
+        let map = Solver::parse(EXAMPLE).unwrap();
+
+        for route in [route_from_start(&map), route_from_lowest(&map)] {
+            let route = route.unwrap();
+            assert_eq!(*route.last().unwrap(), map.end);
+            for pair in route.windows(2) {
+                let (from, to) = (pair[0], pair[1]);
+                assert_eq!(from.0.abs_diff(to.0) + from.1.abs_diff(to.1), 1);
+                assert!(map.can_climb(from, to));
             }
         }
     }
 
-    (start.unwrap(), end.unwrap())
+    #[test]
+    fn render() {
+        let map = HeightMap::parse("Sbcdefghi\nrqponmlkj\nstuvwxyzE").unwrap();
+        let route = route_from_start(&map).unwrap();
+
+        assert_eq!(
+            render_route(&map, &route),
+            ">>>>>>>>v\nv<<<<<<<<\n>>>>>>>>E\n"
+        );
+
+        // the nearest 'a' is S itself
+        let explained = Solver::explain(&map, Stage::Hard).unwrap();
+        assert_eq!(explained.unwrap(), render_route(&map, &route));
+    }
+
+    #[test]
+    fn unreachable_from_s() {
+        // S can't climb to z, but the a right after it can walk the whole alphabet
+        let map = Solver::parse("SzabcdefghijklmnopqrstuvwxyE").unwrap();
+
+        let err = Solver::part1(&map).unwrap_err();
+        assert_eq!(err.message, "the best signal location is unreachable");
+        assert_eq!(Solver::part2(&map).unwrap(), 25.into());
+    }
+
+    #[test]
+    fn markers() {
+        let err = HeightMap::parse("Sab\nabc").unwrap_err();
+        assert_eq!(err.message, "the map has no 'E' marker");
+
+        let err = HeightMap::parse("SaE\naSc").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(2)));
+        assert_eq!(err.message, "the map has more than one 'S' marker");
+    }
+
+    #[test]
+    fn start_counts_as_elevation_a() {
+        // the other 'a' is next to the 'c' but too low to climb it
+        let map = HeightMap::parse("Sbca\nEzzz").unwrap();
+        let to_c = map.routes_to([(0, 2)]);
+
+        assert_eq!(
+            to_c.route_from_height(&map, b'a').unwrap(),
+            [(0, 0), (0, 1), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn routes_from_any_height() {
+        let map = HeightMap::parse(EXAMPLE).unwrap();
+        let to_end = map.routes_to_end();
+
+        let from_s = to_end.route_from_height(&map, b's').unwrap();
+        assert_eq!(from_s.len() - 1, 10);
+        // E itself is the highest
+        assert_eq!(to_end.route_from_height(&map, b'z').unwrap(), [map.end]);
+
+        // everything flows into either of two targets
+        let to_either = map.routes_to([map.end, (4, 7)]);
+        assert_eq!(to_either.route_from((4, 6)).unwrap(), [(4, 6), (4, 7)]);
+    }
 }
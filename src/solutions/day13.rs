@@ -1,52 +1,72 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
 use crate::solutions::day13::Node::{Int, List};
 use crate::solutions::day13::Token::{BrClose, BrOpen, Comma, Invalid, Num};
-use crate::Stage;
-use anyhow::anyhow;
+use crate::solutions::Solution;
 use std::cmp::Ordering;
 use std::cmp::Ordering::Equal;
-use std::str::FromStr;
-
-pub fn solve(stage: Stage, input: &str) -> String {
-    let mut parsed: Vec<Node> = input
-        .lines()
-        .filter_map(|r| {
-            if r.len() > 0 {
-                Some(r.parse().unwrap())
-            } else {
-                None
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Node>;
+
+    fn parse(input: &str) -> Result<Vec<Node>, SolveError> {
+        let mut packets = Vec::new();
+        // the first packet of a pair still waiting for the second one
+        let mut unpaired = None;
+        for line in lines(input) {
+            if line.is_empty() {
+                check_paired(unpaired)?;
+                continue;
             }
-        })
-        .collect();
 
-    match stage {
-        Stage::Easy => solve_easy(parsed),
-        Stage::Hard => solve_hard(&mut parsed),
+            packets.push(Node::parse(line)?);
+            unpaired = match unpaired {
+                None => Some(line),
+                Some(_) => None,
+            };
+        }
+        check_paired(unpaired)?;
+
+        Ok(packets)
+    }
+
+    fn part1(packets: &Vec<Node>) -> Result<Answer, SolveError> {
+        Ok(solve_easy(packets).into())
+    }
+
+    fn part2(packets: &Vec<Node>) -> Result<Answer, SolveError> {
+        Ok(solve_hard(packets).into())
+    }
+}
+
+fn check_paired(unpaired: Option<Line>) -> Result<(), SolveError> {
+    match unpaired {
+        Some(line) => Err(line.error(line.text, "the packet has no pair")),
+        None => Ok(()),
     }
-    .to_string()
 }
 
-fn solve_hard(packets: &mut Vec<Node>) -> usize {
-    let p1: Node = "[[2]]".parse().unwrap();
-    let p2: Node = "[[6]]".parse().unwrap();
-    
+fn solve_hard(packets: &[Node]) -> usize {
+    let mut packets = packets.to_vec();
+    let divider = |v| List(vec![List(vec![Int(v)])]);
+    let p1 = divider(2);
+    let p2 = divider(6);
+
     packets.push(p1.clone());
     packets.push(p2.clone());
 
     packets.sort();
 
-    let p1 = packets
-        .iter()
-        .position(|it| *it == p1)
-        .unwrap();
-    let p2 = packets
-        .iter()
-        .position(|it| *it == p2)
-        .unwrap();
+    let p1 = packets.iter().position(|it| *it == p1).unwrap();
+    let p2 = packets.iter().position(|it| *it == p2).unwrap();
 
     (p1 + 1) * (p2 + 1)
 }
 
-fn solve_easy(packets: Vec<Node>) -> usize {
+fn solve_easy(packets: &[Node]) -> usize {
     let filter_map: Vec<_> = packets
         .chunks(2)
         .enumerate()
@@ -58,7 +78,7 @@ fn solve_easy(packets: Vec<Node>) -> usize {
 }
 
 #[derive(Debug, Clone)]
-enum Node {
+pub enum Node {
     List(Vec<Node>),
     Int(i32),
 }
@@ -73,52 +93,50 @@ impl PartialEq<Self> for Node {
 
 impl PartialOrd<Self> for Node {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        return match self {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self {
             Int(int_self) => match other {
-                Int(int_other) => int_self.partial_cmp(int_other),
-                _ => List(vec![Int(*int_self)]).partial_cmp(other),
+                Int(int_other) => int_self.cmp(int_other),
+                _ => List(vec![Int(*int_self)]).cmp(other),
             },
             List(list_self) => match other {
-                Int(int_other) => {
-                    Some(List(vec![Int(*int_other)]).cmp(self).reverse())
-                }
+                Int(int_other) => List(vec![Int(*int_other)]).cmp(self).reverse(),
                 List(list_other) => {
                     for (n_self, n_other) in list_self.iter().zip(list_other) {
                         let cmp = n_self.cmp(n_other);
                         if cmp != Equal {
-                            return Some(cmp);
+                            return cmp;
                         }
                     }
 
-                    list_self.len().partial_cmp(&list_other.len())
+                    list_self.len().cmp(&list_other.len())
                 }
             },
-        };
-    }
-}
-
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        }
     }
 }
 
-impl FromStr for Node {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Node {
+    fn parse(line: Line) -> Result<Node, SolveError> {
         let mut list_stack: Vec<Vec<Node>> = vec![Vec::new()]; // implicit top-level list
 
-        for token in TokenIterator::new(s) {
+        for (col, token) in TokenIterator::new(line.text) {
+            let err = |msg: &str| Err(SolveError::at(line.no, col, msg));
+
             match token {
-                Invalid(pos) => return Err(anyhow!("invalid token at: {pos}")),
+                Invalid => return err("invalid token"),
                 Comma => continue,
                 BrOpen => list_stack.push(Vec::new()),
                 BrClose => {
-                    let list = list_stack.pop().ok_or(anyhow!("unexpected end of list"))?;
-                    let top = list_stack
-                        .last_mut()
-                        .ok_or(anyhow!("unexpected end of list"))?;
+                    let list = list_stack.pop().unwrap();
+                    let Some(top) = list_stack.last_mut() else {
+                        return err("unexpected end of list");
+                    };
                     top.push(List(list));
                 }
                 Num(n) => {
@@ -128,11 +146,17 @@ impl FromStr for Node {
             }
         }
 
-        if list_stack.len() > 1 {
-            Err(anyhow!("{} ']' tokens expected", list_stack.len() - 1))
-        } else {
-            Ok(list_stack.pop().unwrap().pop().unwrap())
+        if list_stack.len() != 1 {
+            let msg = format!("{} ']' tokens expected", list_stack.len() - 1);
+            return Err(line.error(line.end(), msg));
+        }
+
+        let mut top = list_stack.pop().unwrap();
+        if top.len() != 1 {
+            return Err(line.error(line.text, "expected exactly one packet"));
         }
+
+        Ok(top.pop().unwrap())
     }
 }
 
@@ -141,7 +165,7 @@ enum Token {
     BrClose,
     Comma,
     Num(i32),
-    Invalid(usize),
+    Invalid,
 }
 
 struct TokenIterator<'a> {
@@ -152,7 +176,8 @@ struct TokenIterator<'a> {
 // Yeah, it's primitive but it works
 
 impl<'a> Iterator for TokenIterator<'a> {
-    type Item = Token;
+    // 1-based column where the token starts, and the token itself
+    type Item = (usize, Token);
 
     fn next(&mut self) -> Option<Self::Item> {
         let current_char = self.s.as_bytes().get(self.pos);
@@ -161,34 +186,92 @@ impl<'a> Iterator for TokenIterator<'a> {
             None => None,
             Some(char) => {
                 self.pos += 1;
+                let col = self.pos;
+
+                Some((
+                    col,
+                    match char {
+                        b',' => Comma,
+                        b'[' => BrOpen,
+                        b']' => BrClose,
+                        b'0'..=b'9' => {
+                            let mut result: i32 = (char - b'0') as i32;
 
-                Some(match char {
-                    b',' => Comma,
-                    b'[' => BrOpen,
-                    b']' => BrClose,
-                    b'0'..=b'9' => {
-                        let mut result: i32 = (char - b'0') as i32;
+                            while let Some(char) = self.s.as_bytes().get(self.pos) {
+                                if !char.is_ascii_digit() {
+                                    break;
+                                }
+                                result = result * 10 + ((char - b'0') as i32);
 
-                        while let Some(char) = self.s.as_bytes().get(self.pos) {
-                            if !char.is_ascii_digit() {
-                                break;
+                                self.pos += 1;
                             }
-                            result = result * 10 + ((char - b'0') as i32);
 
-                            self.pos += 1;
+                            Num(result)
                         }
-
-                        Num(result)
-                    }
-                    _ => Invalid(self.pos),
-                })
+                        _ => Invalid,
+                    },
+                ))
             }
         }
     }
 }
 
 impl<'a> TokenIterator<'a> {
-    fn new(s: &str) -> TokenIterator {
+    fn new(s: &str) -> TokenIterator<'_> {
         TokenIterator { s, pos: 0 }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]";
+
+    #[test]
+    fn example() {
+        let packets = Solver::parse(EXAMPLE).unwrap();
+
+        let in_order: Vec<bool> = packets.chunks(2).map(|p| p[0] <= p[1]).collect();
+        assert_eq!(
+            in_order,
+            [true, true, false, true, false, true, false, false]
+        );
+
+        assert_eq!(Solver::part1(&packets).unwrap(), 13.into());
+        assert_eq!(Solver::part2(&packets).unwrap(), 140.into());
+    }
+
+    #[test]
+    fn unpaired_packets() {
+        let err = Solver::parse("[1]\n[2]\n\n[3]").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), Some(1)));
+        assert_eq!(err.message, "the packet has no pair");
+
+        let err = Solver::parse("[1]\n\n[2]\n[3]").unwrap_err();
+        assert_eq!(err.line, Some(1));
+    }
+}
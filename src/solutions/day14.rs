@@ -1,24 +1,38 @@
 use std::cmp::{max, min};
 use std::ops::RangeInclusive;
 
-use crate::solutions::common::{MyRange, RangeSet};
-use crate::Stage;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::common::point::Point2;
+use crate::solutions::common::RangeSet;
+use crate::solutions::Solution;
 
 type Map = Vec<RangeSet>;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let height_offset = 2;
-    let ProblemInput {
-        mut map,
-        col_offset,
-        row_range,
-    } = load_base_map(input, 500, height_offset);
+const HEIGHT_OFFSET: isize = 2;
 
-    if stage.is_hard() {
-        add_floor(&mut map, row_range.end() + height_offset);
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = ProblemInput;
+
+    fn parse(input: &str) -> Result<ProblemInput, SolveError> {
+        load_base_map(input, 500, HEIGHT_OFFSET)
+    }
+
+    fn part1(base: &ProblemInput) -> Result<Answer, SolveError> {
+        let mut map = base.map.clone();
+
+        Ok(sim_sand_fall(&mut map, (base.col_offset, 0)).into())
     }
 
-    sim_sand_fall(&mut map, (col_offset, 0)).to_string()
+    fn part2(base: &ProblemInput) -> Result<Answer, SolveError> {
+        let mut map = base.map.clone();
+        add_floor(&mut map, base.row_range.end() + HEIGHT_OFFSET);
+
+        Ok(sim_sand_fall(&mut map, (base.col_offset, 0)).into())
+    }
 }
 
 fn add_floor(map: &mut Map, target_h: isize) {
@@ -39,7 +53,7 @@ fn sim_sand_fall(map: &mut Map, spawn_point: (isize, isize)) -> isize {
         let mut stop: isize;
         loop {
             let maybe_col = map.get_mut(spawn_col as usize);
-            if let None = maybe_col {
+            if maybe_col.is_none() {
                 // empty column reached, time to stop
                 break 'outer;
             }
@@ -48,16 +62,16 @@ fn sim_sand_fall(map: &mut Map, spawn_point: (isize, isize)) -> isize {
 
             let maybe_stop = get_next_stop(col, spawn_height);
 
-            if let None = maybe_stop {
+            if maybe_stop.is_none() {
                 // no ground below, time to stop
                 break 'outer;
             }
 
             stop = maybe_stop.unwrap();
             spawn_height = stop;
-            if is_empty(&map, spawn_col - 1, spawn_height) {
+            if is_empty(map, spawn_col - 1, spawn_height) {
                 spawn_col -= 1;
-            } else if is_empty(&map, spawn_col + 1, spawn_height) {
+            } else if is_empty(map, spawn_col + 1, spawn_height) {
                 spawn_col += 1;
             } else {
                 break;
@@ -73,24 +87,13 @@ fn sim_sand_fall(map: &mut Map, spawn_point: (isize, isize)) -> isize {
         if col < 0 || col >= map.len() as isize {
             return true;
         }
-        let ranges = map[col as usize].ranges();
-        for range in ranges {
-            if range.from <= height {
-                if range.to >= height {
-                    return false;
-                }
-            } else {
-                break;
-            }
-        }
-
-        true
+        !map[col as usize].contains(height)
     }
 
     fn get_next_stop(col: &RangeSet, start: isize) -> Option<isize> {
         for r in col.ranges() {
-            if r.from > start {
-                return Some(r.from);
+            if r.start() > start {
+                return Some(r.start());
             }
         }
 
@@ -100,23 +103,58 @@ fn sim_sand_fall(map: &mut Map, spawn_point: (isize, isize)) -> isize {
     cnt
 }
 
-fn load_base_map(input: &str, base_col_offset: isize, height_offset: isize) -> ProblemInput {
-    let paths: Vec<Vec<MyRange>> = input
-        .lines()
+fn load_base_map(
+    input: &str,
+    base_col_offset: isize,
+    height_offset: isize,
+) -> Result<ProblemInput, SolveError> {
+    // the corners, with x as the column and y as the height
+    let paths: Vec<Vec<Point2>> = lines(input)
         .map(|row| {
-            row.split(" -> ")
-                .map(|c| {
-                    let mut split = c.split(',').map(|s| s.parse().unwrap());
-                    
-                    MyRange::from((split.next().unwrap(), split.next().unwrap()))
-                })
-                .collect()
-        })
-        .collect();
+            let mut path: Vec<Point2> = Vec::new();
+            for c in row.text.split(" -> ") {
+                let (col, height) = c
+                    .split_once(',')
+                    .ok_or_else(|| row.error(c, "expected a point like '498,4'"))?;
+                let height = match row.parse(height)? {
+                    h if h < 0 => {
+                        return Err(row.error(height, "rocks can't be above the source of sand"));
+                    }
+                    h => h,
+                };
 
-    let max_h = paths.iter().flatten().map(|r| r.to).max().unwrap();
-    let min_col = paths.iter().flatten().map(|r| r.from).min().unwrap();
-    let max_col = paths.iter().flatten().map(|r| r.from).max().unwrap();
+                let corner = Point2::new(row.parse(col)?, height);
+                if let Some(prev) = path.last() {
+                    if prev.x != corner.x && prev.y != corner.y {
+                        return Err(row.error(c, "rock paths only go straight across or down"));
+                    }
+                }
+                path.push(corner);
+            }
+
+            Ok(path)
+        })
+        .collect::<Result<_, _>>()?;
+
+    let no_rocks = || SolveError::new("no rock paths in the scan");
+    let max_h = paths
+        .iter()
+        .flatten()
+        .map(|p| p.y)
+        .max()
+        .ok_or_else(no_rocks)?;
+    let min_col = paths
+        .iter()
+        .flatten()
+        .map(|p| p.x)
+        .min()
+        .ok_or_else(no_rocks)?;
+    let max_col = paths
+        .iter()
+        .flatten()
+        .map(|p| p.x)
+        .max()
+        .ok_or_else(no_rocks)?;
 
     let min_col = min(min_col, base_col_offset - (height_offset + max_h));
     let max_col = max(max_col, base_col_offset + (height_offset + max_h));
@@ -130,39 +168,65 @@ fn load_base_map(input: &str, base_col_offset: isize, height_offset: isize) -> P
     for path in paths {
         for pair in path.windows(2) {
             let [mut start, mut end] = pair else { panic!() };
-            start.from -= min_col;
-            end.from -= min_col;
+            start.x -= min_col;
+            end.x -= min_col;
 
-            let diff = (end.from.cmp(&start.from) as isize, end.to.cmp(&start.to) as isize);
+            let diff = (end - start).signum();
             let mut curr = start;
 
-            if diff.0 == 0 {
-                let col: &mut _ = &mut map[curr.from as usize];
-                col.insert((start.to, end.to).into());
+            if diff.x == 0 {
+                let col: &mut _ = &mut map[curr.x as usize];
+                col.insert((start.y, end.y).into());
             } else {
                 loop {
-                    let col: &mut _ = &mut map[curr.from as usize];
-                    col.insert(curr.to.into());
+                    let col: &mut _ = &mut map[curr.x as usize];
+                    col.insert(curr.y.into());
 
                     if curr == end {
                         break;
                     }
-                    curr.from += diff.0;
-                    curr.to += diff.1;
+                    curr += diff;
                 }
             }
         }
     }
 
-    ProblemInput {
+    Ok(ProblemInput {
         map,
         col_offset: base_col_offset - min_col,
         row_range: 0..=max_h,
-    }
+    })
 }
 
-struct ProblemInput {
+pub struct ProblemInput {
     map: Map,
     col_offset: isize,
     row_range: RangeInclusive<isize>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+    #[test]
+    fn example() {
+        let base = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(base.row_range, 0..=9);
+        assert_eq!(Solver::part1(&base).unwrap(), 24.into());
+        assert_eq!(Solver::part2(&base).unwrap(), 93.into());
+    }
+
+    #[test]
+    fn diagonal_paths() {
+        let err = Solver::parse("498,4 -> 498,6\n498,4 -> 500,5")
+            .err()
+            .unwrap();
+        assert_eq!((err.line, err.column), (Some(2), Some(10)));
+        assert_eq!(err.message, "rock paths only go straight across or down");
+    }
+}
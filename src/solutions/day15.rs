@@ -1,15 +1,57 @@
-use std::str::FromStr;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
-use std::sync::{Arc, mpsc};
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::common::point::Point2;
+use crate::solutions::common::region::Region;
+use crate::solutions::common::MyRange;
+use crate::solutions::{Params, Solution};
 
-use anyhow::anyhow;
+pub struct Solver;
 
-use crate::solutions::common::RangeSet;
-use crate::Stage;
+impl Solution for Solver {
+    type Parsed<'a> = Report;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let (sensors, beacons_src): (Vec<_>, Vec<_>) = input.lines().map(parse_row).unzip();
+    fn parse(input: &str) -> Result<Report, SolveError> {
+        read_report(input)
+    }
+
+    fn configure(report: &mut Report, params: &Params) -> Result<(), SolveError> {
+        params.check_names(&["target-row", "max"])?;
+
+        report.target_row = params.get("target-row", report.target_row)?;
+        report.pos_max = params.get("max", report.pos_max)?;
+
+        Ok(())
+    }
+
+    fn part1(report: &Report) -> Result<Answer, SolveError> {
+        Ok(solve_easy(&report.sensors, &report.beacons, report.target_row).into())
+    }
+
+    fn part2(report: &Report) -> Result<Answer, SolveError> {
+        Ok(solve_hard(&report.sensors, report.pos_max)?.into())
+    }
+}
+
+// the example asks about row 10 and coordinates up to 20 instead
+const TARGET_ROW: isize = 2000000;
+const POS_MAX: isize = 4000000;
+
+/// The sensors and the distinct beacons they detect, along with the row the first stage counts
+/// and the largest coordinate the distress beacon can have.
+pub struct Report {
+    sensors: Vec<Sensor>,
+    beacons: Vec<Point2>,
+    target_row: isize,
+    pos_max: isize,
+}
+
+fn read_report(input: &str) -> Result<Report, SolveError> {
+    let (sensors, beacons_src): (Vec<_>, Vec<_>) = lines(input)
+        .map(parse_row)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
     let mut beacons: Vec<Point2> = Vec::with_capacity(beacons_src.len());
 
     for b in beacons_src {
@@ -18,118 +60,269 @@ pub fn solve(stage: Stage, input: &str) -> String {
         }
     }
 
-    match stage {
-        Stage::Easy => solve_easy(&sensors, &beacons),
-        Stage::Hard => solve_hard(&sensors),
-    }
-    .to_string()
+    Ok(Report {
+        sensors,
+        beacons,
+        target_row: TARGET_ROW,
+        pos_max: POS_MAX,
+    })
 }
 
-fn solve_easy(sensors: &[Sensor], beacons: &[Point2]) -> usize {
-    let mut range_set = RangeSet::with_capacity(sensors.len());
+fn solve_easy(sensors: &[Sensor], beacons: &[Point2], target_row: isize) -> usize {
+    let mut range_set = coverage(sensors).row(target_row);
 
-    const TARGET_ROW: isize = 2000000;
+    for b in beacons.iter().filter(|b| b.y == target_row) {
+        range_set.remove(b.x.into());
+    }
+
+    range_set.count()
+}
 
-    fill_range_set_for_row(&mut range_set, sensors, TARGET_ROW);
+fn solve_hard(sensors: &[Sensor], pos_max: isize) -> Result<usize, SolveError> {
+    let area =
+        MyRange::try_new(0, pos_max).ok_or_else(|| SolveError::new("the search area is empty"))?;
 
-    let cnt: usize = range_set.ranges().iter().map(|r| r.count()).sum();
-    let intersecting_beacons = beacons
-        .iter()
-        .filter(|b| b.y == TARGET_ROW && range_set.contains(b.x))
-        .count();
+    match candidates(sensors, area)[..] {
+        [] => Err(SolveError::new(
+            "the sensors cover every possible beacon position",
+        )),
+        [beacon] => tuning_frequency(beacon),
+        // the crossings only outline what is left, so list the cells themselves
+        _ => {
+            const SHOWN: usize = 10;
+            let left: Vec<_> = coverage(sensors)
+                .uncovered(area, area)
+                .take(SHOWN + 1)
+                .collect();
+            let positions: Vec<String> = left
+                .iter()
+                .take(SHOWN)
+                .map(|(x, y)| format!("x={x}, y={y}"))
+                .collect();
 
-    cnt - intersecting_beacons
+            Err(SolveError::new(match left.len() {
+                n if n > SHOWN => format!(
+                    "the sensors leave more than {SHOWN} possible beacon positions, \
+                     starting with {}",
+                    positions.join("; ")
+                ),
+                n => format!(
+                    "the sensors leave {n} possible beacon positions: {}",
+                    positions.join("; ")
+                ),
+            }))
+        }
+    }
 }
 
-fn solve_hard(sensors: &[Sensor]) -> usize {
-    const POS_MAX: isize = 4000000;
+fn tuning_frequency(beacon: Point2) -> Result<usize, SolveError> {
     const X_MUL: isize = 4000000;
-    let n_threads = thread::available_parallelism().unwrap().get();
-    
-    let (tx, rx) = mpsc::channel();
-    let found = Arc::new(AtomicBool::new(false));
-
-    let _threads: Vec<_> = (0..n_threads)
-        .map(|i| {
-            let sensors: Vec<_> = sensors.iter().map(|x| x.clone()).collect();
-            let tx = tx.clone();
-            let found = Arc::clone(&found);
-            thread::spawn(move || {
-                let mut range_set = RangeSet::with_capacity(sensors.len());
-
-                for row in (0isize..=POS_MAX - i as isize).rev().step_by(n_threads) {
-                    if found.load(Ordering::Acquire) { return }
-
-                    fill_range_set_for_row(&mut range_set, &sensors, row);
-                    for range in range_set.ranges() {
-                        if range.to >= 0 && range.to < POS_MAX {
-                            tx.send(X_MUL * (range.to + 1) + row).unwrap();
-                            found.store(true, Ordering::Release)
-                        }
-                    }
-                }
-            })
-        })
-        .collect();
 
-    rx.recv().unwrap() as usize
+    X_MUL
+        .checked_mul(beacon.x)
+        .and_then(|x| x.checked_add(beacon.y))
+        .map(|freq| freq as usize)
+        .ok_or_else(|| SolveError::new("the tuning frequency overflows"))
 }
 
-fn fill_range_set_for_row(range_set: &mut RangeSet, sensors: &[Sensor], row: isize) {
-    range_set.clear();
+/// The uncovered cells within `area` on both axes where the distress beacon can hide, in
+/// order. The beacon is the only uncovered cell, so every side of it touches the range of a
+/// sensor or the edge of the area. That puts it where two of the lines running just out of
+/// the sensors' reach cross, or where such a line meets an edge, or in a corner.
+fn candidates(sensors: &[Sensor], area: MyRange) -> Vec<Point2> {
+    // the lines as x + y = sum and x - y = diff, wide enough for any sensor position
+    let mut sums = Vec::with_capacity(sensors.len() * 2);
+    let mut diffs = Vec::with_capacity(sensors.len() * 2);
+    for s in sensors {
+        let (x, y, reach) = (s.pos.x as i128, s.pos.y as i128, s.range as i128 + 1);
+        sums.extend([x + y - reach, x + y + reach]);
+        diffs.extend([x - y - reach, x - y + reach]);
+    }
 
-    for sensor in sensors {
-        let row_diff = (sensor.pos.y - row).abs();
-        let row_range = sensor.range - row_diff;
+    let edges = [area.start() as i128, area.end() as i128];
+    let mut points: Vec<(i128, i128)> = edges
+        .into_iter()
+        .flat_map(|x| edges.map(|y| (x, y)))
+        .collect();
+    for &sum in &sums {
+        let crossings = diffs.iter().filter(|&&diff| (sum + diff) % 2 == 0);
+        points.extend(crossings.map(|&diff| ((sum + diff) / 2, (sum - diff) / 2)));
+        points.extend(edges.into_iter().flat_map(|e| [(e, sum - e), (sum - e, e)]));
+    }
+    for &diff in &diffs {
+        points.extend(
+            edges
+                .into_iter()
+                .flat_map(|e| [(e, e - diff), (e + diff, e)]),
+        );
+    }
 
-        if row_range >= 0 {
-            range_set.insert((sensor.pos.x - row_range, sensor.pos.x + row_range).into())
-        }
+    let mut result: Vec<Point2> = points
+        .into_iter()
+        .filter_map(|(x, y)| Some(Point2::new(x.try_into().ok()?, y.try_into().ok()?)))
+        .filter(|p| area.contains(p.x) && area.contains(p.y))
+        .filter(|&p| !sensors.iter().any(|s| s.covers(p)))
+        .collect();
+    result.sort_by_key(|p| (p.y, p.x));
+    result.dedup();
+
+    result
+}
+
+// the cells where the beacon can't be as it is farther than the closest one
+fn coverage(sensors: &[Sensor]) -> Region {
+    let mut region = Region::new();
+    for sensor in sensors {
+        region.insert_diamond((sensor.pos.x, sensor.pos.y), sensor.range);
     }
+
+    region
 }
 
-fn parse_row(row: &str) -> (Sensor, Point2) {
-    let mut split = row.split(": ");
-    let sensor_pos = split.next().unwrap()[10..].parse().unwrap();
-    let beacon_pos = split.next().unwrap()[21..].parse().unwrap();
+fn parse_row(row: Line) -> Result<(Sensor, Point2), SolveError> {
+    let rest = row.strip_prefix(row.text, "Sensor at ")?;
+    let (sensor_pos, beacon_pos) = row.split_once(rest, ": closest beacon is at ")?;
+    let sensor_pos = parse_point(row, sensor_pos)?;
+    let beacon_pos = parse_point(row, beacon_pos)?;
+
+    let range = sensor_pos
+        .manhattan_dist(beacon_pos)
+        .ok_or_else(|| row.error(row.text, "the beacon is too far from the sensor"))?;
 
-    (
+    Ok((
         Sensor {
             pos: sensor_pos,
-            range: sensor_pos.manhattan_dist(&beacon_pos),
+            range,
         },
         beacon_pos,
-    )
+    ))
 }
 
 #[derive(Debug, Copy, Clone, Default)]
-struct Sensor {
+pub struct Sensor {
     pos: Point2,
     range: isize,
 }
 
-#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
-struct Point2 {
-    x: isize,
-    y: isize,
+impl Sensor {
+    fn covers(&self, p: Point2) -> bool {
+        self.pos.manhattan_dist(p).is_some_and(|d| d <= self.range)
+    }
 }
 
-impl Point2 {
-    fn manhattan_dist(&self, other: &Self) -> isize {
-        (other.x - self.x).abs() + (other.y - self.y).abs()
-    }
+// parses "x=..., y=..." part of the row
+fn parse_point(row: Line, s: &str) -> Result<Point2, SolveError> {
+    let x = row.strip_prefix(s, "x=")?;
+    let (x, y) = row.split_once(x, ", ")?;
+    let y = row.strip_prefix(y, "y=")?;
+
+    Ok(Point2::new(row.parse(x)?, row.parse(y)?))
 }
 
-impl FromStr for Point2 {
-    type Err = anyhow::Error;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+    #[test]
+    fn example() {
+        let mut report = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(report.beacons.len(), 6);
+        assert_eq!(report.sensors[6].range, 9);
+
+        // the example asks about a smaller area than the real puzzle
+        let mut params = Params::new();
+        params.set("target-row", "10");
+        params.set("max", "20");
+        Solver::configure(&mut report, &params).unwrap();
+        assert_eq!(Solver::part1(&report).unwrap(), 26.into());
+        assert_eq!(Solver::part2(&report).unwrap(), 56000011.into());
 
-    // assumes "x=..., y=..." string
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(", ").map(|s| s[2..].parse());
+        params.set("min", "0");
+        let err = Solver::configure(&mut report, &params).unwrap_err();
+        assert_eq!(
+            err.message,
+            "unknown parameter 'min', expected one of target-row, max"
+        );
+    }
+
+    #[test]
+    fn candidates_at_the_edges() {
+        let report = Solver::parse(EXAMPLE).unwrap();
+        let area = MyRange::new(0, 20);
+        assert_eq!(candidates(&report.sensors, area), [Point2::new(14, 11)]);
+
+        // all but the far corner is in range
+        let sensors = [Sensor {
+            pos: Point2::new(0, 0),
+            range: 5,
+        }];
+        assert_eq!(
+            candidates(&sensors, MyRange::new(0, 3)),
+            [Point2::new(3, 3)]
+        );
+        assert_eq!(solve_hard(&sensors, 3).unwrap(), 12000003);
+        // and a bit closer, the whole area is
+        assert_eq!(
+            solve_hard(&sensors, 2).unwrap_err().message,
+            "the sensors cover every possible beacon position"
+        );
+        // while a bit further, the beacon could be in more than one place
+        assert_eq!(
+            solve_hard(&sensors, 4).unwrap_err().message,
+            "the sensors leave 6 possible beacon positions: \
+             x=4, y=2; x=3, y=3; x=4, y=3; x=2, y=4; x=3, y=4; x=4, y=4"
+        );
+        // and without sensors, it could be anywhere
+        let err = solve_hard(&[], 20).unwrap_err();
+        assert!(err.message.starts_with(
+            "the sensors leave more than 10 possible beacon positions, \
+             starting with x=0, y=0; x=1, y=0;"
+        ));
+
+        // the ranges meet just short of the middle of the right edge
+        let sensors = [
+            Sensor {
+                pos: Point2::new(0, 2),
+                range: 3,
+            },
+            Sensor {
+                pos: Point2::new(3, 0),
+                range: 2,
+            },
+            Sensor {
+                pos: Point2::new(3, 4),
+                range: 2,
+            },
+        ];
+        assert_eq!(
+            candidates(&sensors, MyRange::new(0, 4)),
+            [Point2::new(4, 2)]
+        );
+    }
 
-        Ok(Point2 {
-            x: iter.next().ok_or(anyhow!("can't find x"))??,
-            y: iter.next().ok_or(anyhow!("can't find y"))??,
-        })
+    #[test]
+    fn far_beacon() {
+        let row = format!(
+            "Sensor at x={}, y=0: closest beacon is at x=1, y=0",
+            isize::MIN
+        );
+        assert!(Solver::parse(&row).is_err());
     }
 }
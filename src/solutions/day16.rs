@@ -1,225 +1,785 @@
-use std::cmp::max;
-use std::iter;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::num::NonZeroUsize;
+use std::thread;
 
 use ahash::AHashMap;
 
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line, Tokens};
+use crate::solutions::common::search::AllPairs;
+use crate::solutions::{Params, Solution};
 use crate::Stage;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let g = read_graph(input);
-    let (time, split) = match stage {
-        Stage::Easy => (30, false),
-        Stage::Hard => (26, true),
-    };
+pub struct Solver;
 
-    search(g, time, split).to_string()
+impl Solution for Solver {
+    type Parsed<'a> = Volcano;
+
+    fn parse(input: &str) -> Result<Volcano, SolveError> {
+        Ok(Volcano {
+            g: read_graph(input)?,
+            start: START.to_string(),
+            minutes: MINUTES,
+            actors: ACTORS,
+        })
+    }
+
+    /// Takes the valve to start from as `start`, the minutes to go for as `minutes` alone and
+    /// `elephant-minutes` with the elephant, and how many open the valves together in the
+    /// second stage as `actors`.
+    fn configure(v: &mut Volcano, params: &Params) -> Result<(), SolveError> {
+        params.check_names(&["start", "minutes", "elephant-minutes", "actors"])?;
+
+        v.start = params.get("start", v.start.clone())?;
+        v.minutes = [
+            params.get("minutes", v.minutes[0])?,
+            params.get("elephant-minutes", v.minutes[1])?,
+        ];
+        v.actors = match params.get("actors", v.actors)? {
+            0 => {
+                return Err(SolveError::new(
+                    "someone has to open the valves, actors can't be 0",
+                ))
+            }
+            actors => actors,
+        };
+
+        Ok(())
+    }
+
+    fn part1(v: &Volcano) -> Result<Answer, SolveError> {
+        Ok(v.plan(Stage::Easy)?.released().into())
+    }
+
+    fn part2(v: &Volcano) -> Result<Answer, SolveError> {
+        Ok(v.plan(Stage::Hard)?.released().into())
+    }
+
+    fn explain(v: &Volcano, stage: Stage) -> Result<Option<String>, SolveError> {
+        Ok(Some(v.plan(stage)?.timeline(&v.g)))
+    }
+}
+
+const START: &str = "AA";
+// it takes 4 minutes to teach the elephant
+const MINUTES: [i64; 2] = [30, 26];
+// you and the elephant
+const ACTORS: usize = 2;
+
+/// The valves, along with where the actors start and how long each stage gives them.
+pub struct Volcano {
+    g: Graph,
+    start: String,
+    // alone, then with the elephant
+    minutes: [i64; 2],
+    // working together in the second stage
+    actors: usize,
+}
+
+impl Volcano {
+    fn plan(&self, stage: Stage) -> Result<Plan, SolveError> {
+        let actors = match stage {
+            Stage::Easy => 1,
+            Stage::Hard => self.actors,
+        };
+
+        best_plan(&self.g, &self.start, self.minutes[stage as usize], actors)
+    }
+}
+
+/// Who opens the valves, and the verb that goes with them.
+fn actor(i: usize) -> (String, &'static str) {
+    match i {
+        0 => ("You".to_string(), "open"),
+        1 => ("The elephant".to_string(), "opens"),
+        i => (format!("Elephant #{i}"), "opens"),
+    }
+}
+
+/// When each valve gets opened and by whom.
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    openings: Vec<Opening>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Opening {
+    // the minute spent opening the valve, it releases pressure from the next one on
+    minute: i64,
+    actor: usize,
+    valve: usize,
+    released: i64,
+}
+
+impl Plan {
+    fn released(&self) -> i64 {
+        self.openings.iter().map(|o| o.released).sum()
+    }
+
+    fn timeline(&self, g: &Graph) -> String {
+        let mut openings = self.openings.clone();
+        openings.sort_by_key(|o| (o.minute, o.actor));
+
+        let mut result = String::new();
+        for o in openings {
+            let rate = g.flow_rates[o.valve];
+            let (who, verb) = actor(o.actor);
+            result += &format!(
+                "Minute {}: {who} {verb} valve {} ({rate}/min, {} in total)\n",
+                o.minute, g.names[o.valve], o.released
+            );
+        }
+        result += &format!("Released: {}\n", self.released());
+
+        result
+    }
+}
+
+/// The plan releasing the most pressure within `budget` minutes for `actors` working together,
+/// all of them starting at the valve named `start`.
+fn best_plan(g: &Graph, start: &str, budget: i64, actors: usize) -> Result<Plan, SolveError> {
+    let start = g.index_of(start).ok_or_else(|| {
+        SolveError::new(format_args!("there is no valve '{start}' to start from"))
+    })?;
+
+    // as the actors work separately, they can only help each other by opening distinct valves
+    let subsets = best_by_subset(g, start, budget);
+    let mut by_subset: Vec<(ValveSet, i64)> = subsets
+        .released
+        .iter()
+        .map(|(valves, &released)| (valves.clone(), released))
+        .collect();
+    by_subset.sort_by(|(a, a_released), (b, b_released)| {
+        (Reverse(a_released), a).cmp(&(Reverse(b_released), b))
+    });
+    let mut pick = Pick::default();
+    pick.best_disjoint(&by_subset, 0, &ValveSet::default(), actors, 0);
+
+    let mut plan = Plan::default();
+    for (actor, &i) in pick.best.iter().enumerate() {
+        let (valves, _) = &by_subset[i];
+        plan.openings
+            .extend(
+                subsets
+                    .order(valves, budget)
+                    .into_iter()
+                    .map(|(minute, valve, released)| Opening {
+                        minute,
+                        actor,
+                        valve,
+                        released,
+                    }),
+            );
+    }
+
+    Ok(plan)
 }
 
-fn search(g: Graph, time: i64, split: bool) -> i64 {
-    fn search_impl(
-        g: &Graph,
-        v: usize,
-        visited: u64,
-        mut time_remaining: i64,
-        cache: &mut Cache,
-    ) -> i64 {
-        if time_remaining <= 0 {
-            return 0;
+/// The most pressure a single actor can release by opening each subset of the valves, along
+/// with the explorers that found it, which know the order of the openings.
+struct Subsets<'a> {
+    released: AHashMap<ValveSet, i64>,
+    explorers: Vec<Explorer<'a>>,
+}
+
+impl Subsets<'_> {
+    /// The openings releasing the most pressure with exactly `valves` open, as (minute, valve,
+    /// pressure released).
+    fn order(&self, valves: &ValveSet, budget: i64) -> Vec<(i64, usize, i64)> {
+        let released = self.released[valves];
+
+        // of the equally good orders, a single thread finds the one opening the lowest valve
+        // first, the others are left to the threads starting there
+        self.explorers
+            .iter()
+            .filter_map(|e| e.order(valves, released, budget))
+            .min_by_key(|order| order.first().map(|&(_, v, _)| v))
+            .expect("every subset was reached in some order")
+    }
+}
+
+/// Explores the subsets of the valves. The ways to go are split by the first valve opened
+/// among as many threads as there are cores, each with its own memo, and the results merged
+/// once they are all done, so that the subsets and their pressures don't depend on the number
+/// of threads.
+fn best_by_subset(g: &Graph, start: usize, budget: i64) -> Subsets<'_> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                s.spawn(move || {
+                    let mut explorer = Explorer::new(g, (thread, threads));
+                    explorer.explore(start, budget, 0, None);
+                    explorer
+                })
+            })
+            .collect();
+
+        let explorers: Vec<Explorer> = handles
+            .into_iter()
+            .map(|handle| handle.join().expect("the search threads don't panic"))
+            .collect();
+        let mut released = AHashMap::new();
+        for explorer in &explorers {
+            for (valves, &(r, _)) in &explorer.best {
+                let b = released.entry(valves.clone()).or_insert(r);
+                *b = r.max(*b);
+            }
         }
 
-        if let Some(cached) = cache.get(v, visited, time_remaining) {
-            return cached;
+        Subsets {
+            released,
+            explorers,
         }
+    })
+}
+
+/// Walks through every way a single actor can open the valves, noting the most pressure each
+/// subset of them can release.
+struct Explorer<'a> {
+    g: &'a Graph,
+    // (n, count): only the valves at positions `n`, `n + count`... get opened first
+    share: (usize, usize),
+    opened: ValveSet,
+    // the most pressure released by opening each subset, and the last opening on the way
+    best: AHashMap<ValveSet, (i64, Option<Step>)>,
+    // the most pressure released on opening a valve with the given time left and valves opened,
+    // and the opening before it. Later ways to the same state that release less can't do any
+    // better from there on
+    seen: AHashMap<(usize, i64, ValveSet), (i64, Option<Step>)>,
+}
 
-        let base = g.flow_rates[v] * time_remaining;
-        time_remaining -= 1;
+// an opened valve, by its position in `Graph::valves`, and the time left after opening it
+type Step = (usize, i64);
+
+impl Explorer<'_> {
+    fn new(g: &Graph, share: (usize, usize)) -> Explorer<'_> {
+        Explorer {
+            g,
+            share,
+            opened: ValveSet::default(),
+            best: AHashMap::new(),
+            seen: AHashMap::new(),
+        }
+    }
 
-        let mut best = 0;
+    fn explore(&mut self, at: usize, time_left: i64, released: i64, last: Option<Step>) {
+        if self
+            .best
+            .get(&self.opened)
+            .is_none_or(|&(b, _)| released > b)
+        {
+            self.best.insert(self.opened.clone(), (released, last));
+        }
 
-        for i in 0..g.non_zero_flow_cnt {
-            if (visited & (1 << i)) != 0 {
+        for (bit, &v) in self.g.valves.iter().enumerate() {
+            if self.opened.contains(bit) {
                 continue;
             }
-            best = max(
-                best,
-                search_impl(
-                    g,
-                    i,
-                    visited | (1 << i),
-                    time_remaining - g.get_len(v, i),
-                    cache,
-                ),
-            )
+            if self.opened.is_empty() && bit % self.share.1 != self.share.0 {
+                continue;
+            }
+            let Some(dist) = self.g.dist(at, v) else {
+                continue;
+            };
+            // walking there and a minute to open it
+            let time_left = time_left - dist - 1;
+            if time_left <= 0 {
+                continue;
+            }
+
+            let released = released + self.g.flow_rates[v] * time_left;
+            self.opened.insert(bit);
+            let worth_it = match self.seen.entry((bit, time_left, self.opened.clone())) {
+                Entry::Occupied(e) if e.get().0 >= released => false,
+                Entry::Occupied(mut e) => {
+                    e.insert((released, last));
+                    true
+                }
+                Entry::Vacant(e) => {
+                    e.insert((released, last));
+                    true
+                }
+            };
+            if worth_it {
+                self.explore(v, time_left, released, Some((bit, time_left)));
+            }
+            self.opened.remove(bit);
         }
+    }
 
-        let result = best + base;
-        cache.set(v, visited, time_remaining + 1, result);
+    /// Follows the openings back from the best one found for `valves`, unless it releases less
+    /// than `released`. As a state only gets a better way to it along with the states after it,
+    /// the openings before each one still lead up to it.
+    fn order(
+        &self,
+        valves: &ValveSet,
+        released: i64,
+        budget: i64,
+    ) -> Option<Vec<(i64, usize, i64)>> {
+        let &(found, mut last) = self.best.get(valves)?;
+        if found != released {
+            return None;
+        }
 
-        result
+        let mut opened = valves.clone();
+        let mut order = Vec::new();
+        while let Some((bit, time_left)) = last {
+            let v = self.g.valves[bit];
+            order.push((budget - time_left, v, self.g.flow_rates[v] * time_left));
+            last = self.seen[&(bit, time_left, opened.clone())].1;
+            opened.remove(bit);
+        }
+        order.reverse();
+
+        Some(order)
     }
+}
 
-    let visited_max = 1u64 << g.non_zero_flow_cnt;
-    let variants_count = match split {
-        true => visited_max >> 1,
-        false => 1,
-    };
+/// The best way to split the work: a subset for each actor, none of them overlapping.
+#[derive(Default)]
+struct Pick {
+    released: i64,
+    // indices of the subsets picked, actors left without one stay idle
+    best: Vec<usize>,
+    current: Vec<usize>,
+}
 
-    let start = g.aa_idx;
-    let mut cache = Cache::new(&g, time);
+impl Pick {
+    /// `subsets` go from the most pressure released to the least.
+    fn best_disjoint(
+        &mut self,
+        subsets: &[(ValveSet, i64)],
+        from: usize,
+        used: &ValveSet,
+        actors_left: usize,
+        released: i64,
+    ) {
+        if released > self.released {
+            self.released = released;
+            self.best = self.current.clone();
+        }
+        if actors_left == 0 {
+            return;
+        }
+
+        for (i, (valves, subset_released)) in subsets.iter().enumerate().skip(from) {
+            // none of the subsets left can make up for the difference
+            if released + subset_released * actors_left as i64 <= self.released {
+                break;
+            }
+            if !valves.is_disjoint(used) {
+                continue;
+            }
+
+            self.current.push(i);
+            let used = used.union(valves);
+            self.best_disjoint(
+                subsets,
+                i + 1,
+                &used,
+                actors_left - 1,
+                released + subset_released,
+            );
+            self.current.pop();
+        }
+    }
+}
+
+/// A set of valves worth opening, by their position in `Graph::valves`.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+struct ValveSet {
+    // trailing zero words are trimmed, so that equal sets compare equal
+    words: Vec<u64>,
+}
+
+impl ValveSet {
+    fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .is_some_and(|w| w & (1 << (i % 64)) != 0)
+    }
+
+    fn insert(&mut self, i: usize) {
+        if self.words.len() <= i / 64 {
+            self.words.resize(i / 64 + 1, 0);
+        }
+        self.words[i / 64] |= 1 << (i % 64);
+    }
 
-    let mut result = 0;
+    fn remove(&mut self, i: usize) {
+        if let Some(w) = self.words.get_mut(i / 64) {
+            *w &= !(1 << (i % 64));
+        }
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
 
-    for mask in 0..variants_count {
-        let total_a = search_impl(&g, start, mask, time, &mut cache);
-        let total_b = search_impl(&g, start, !mask, time, &mut cache);
+    fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
 
-        result = max(result, total_a + total_b);
+    fn is_disjoint(&self, other: &ValveSet) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
     }
 
-    result
+    fn union(&self, other: &ValveSet) -> ValveSet {
+        let (long, short) = match self.words.len() >= other.words.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        let mut words = long.words.clone();
+        for (w, o) in words.iter_mut().zip(&short.words) {
+            *w |= o;
+        }
+
+        ValveSet { words }
+    }
 }
 
-struct Graph {
-    aa_idx: usize,
+pub struct Graph {
+    names: Vec<String>,
     flow_rates: Vec<i64>,
-
-    lengths: Vec<i64>,
-    non_zero_flow_cnt: usize,
+    // the valves worth opening
+    valves: Vec<usize>,
+    lengths: AllPairs<i64>,
 }
 
 impl Graph {
-    fn new(mut flow_rates_src: Vec<(&str, i64)>, edges: Vec<(&str, Vec<&str>)>) -> Graph {
+    fn new(flow_rates_src: Vec<(&str, i64)>, edges: Vec<(&str, Vec<&str>)>) -> Graph {
         let n = flow_rates_src.len();
-        flow_rates_src.sort_by_key(|(_, flow)| -*flow);
 
         let mut index_map = AHashMap::with_capacity(n);
+        let mut names = Vec::with_capacity(n);
         let mut flow_rates = Vec::with_capacity(n);
-        let mut non_zero_flow_cnt = 0;
 
         for (vert, rate) in flow_rates_src {
-            index_map.insert(vert.to_string(), flow_rates.len());
+            index_map.insert(vert, flow_rates.len());
+            names.push(vert.to_string());
             flow_rates.push(rate);
-            non_zero_flow_cnt += (rate > 0) as usize;
         }
+        let valves = (0..n).filter(|&v| flow_rates[v] > 0).collect();
 
-        let aa_idx = index_map["AA"];
-
-        let lengths = Vec::from_iter(iter::repeat(i64::MAX).take(n * n));
-
-        let mut result = Graph {
-            aa_idx,
+        let index_map = &index_map;
+        let tunnels = edges.into_iter().flat_map(|(src, destinations)| {
+            let src = index_map[src];
+            destinations
+                .into_iter()
+                .map(move |dst| (src, index_map[dst], 1))
+        });
+        let lengths = AllPairs::new(n, tunnels);
+
+        Graph {
+            names,
             flow_rates,
+            valves,
             lengths,
-            non_zero_flow_cnt,
-        };
-
-        for (src, destinations) in edges {
-            let src = index_map[src];
-            for dst in destinations {
-                let dst = index_map[dst];
-                result.set_len(src, dst, 1);
-            }
-        }
-
-        for k in 0..n {
-            for i in 0..n {
-                for j in 0..n {
-                    let current = result.get_len(i, j);
-                    let updated = result
-                        .get_len(i, k)
-                        .checked_add(result.get_len(k, j))
-                        .unwrap_or(current);
-                    if current > updated {
-                        result.set_len(i, j, updated)
-                    }
-                }
-            }
         }
+    }
 
-        result
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
     }
 
-    fn vertex_count(&self) -> usize {
-        self.flow_rates.len()
+    // `None` for valves that can't be reached
+    fn dist(&self, from: usize, to: usize) -> Option<i64> {
+        self.lengths.dist(from, to)
     }
+}
+
+fn read_graph(input: &str) -> Result<Graph, SolveError> {
+    let mut flow_rates = Vec::new();
+    let mut edges = Vec::new();
+    let mut defined: AHashMap<&str, Line> = AHashMap::new();
+    let mut tunnel_lines: Vec<(Line, &str)> = Vec::new();
 
-    fn get_len(&self, from: usize, to: usize) -> i64 {
-        self.lengths[from * self.vertex_count() + to]
+    for line in lines(input) {
+        let (valve, flow, tunnels) = parse_valve(line)?;
+
+        if let Some(first) = defined.insert(valve, line) {
+            return Err(line.error(
+                valve,
+                format_args!("valve '{valve}' is already described on line {}", first.no),
+            ));
+        }
+        tunnel_lines.extend(tunnels.iter().map(|&t| (line, t)));
+        flow_rates.push((valve, flow));
+        edges.push((valve, tunnels));
     }
 
-    fn set_len(&mut self, from: usize, to: usize, new_len: i64) {
-        let i = from * self.vertex_count() + to;
-        self.lengths[i] = new_len
+    for (line, dst) in tunnel_lines {
+        if !defined.contains_key(dst) {
+            return Err(line.error(dst, format_args!("tunnel leads to unknown valve '{dst}'")));
+        }
     }
-}
 
-struct Cache {
-    data: Vec<i64>,
-    max_mask: usize,
-    max_time: usize,
-    max_v_idx: usize,
+    Ok(Graph::new(flow_rates, edges))
 }
 
-impl Cache {
-    fn new(g: &Graph, max_time: i64) -> Cache {
-        let max_mask = 1usize << g.non_zero_flow_cnt;
-        let max_v_idx = g.non_zero_flow_cnt + 1;
-        let max_time = max_time as usize;
+/// Reads a line like "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB", valves can
+/// be named anything without whitespace, '=', ';' or ','.
+fn parse_valve<'a>(line: Line<'a>) -> Result<(&'a str, i64, Vec<&'a str>), SolveError> {
+    let mut tokens = Tokens::new(line, "=;,");
 
-        Cache {
-            data: iter::repeat(-1)
-                .take(max_mask * max_time * (max_v_idx + 1))
-                .collect(),
-            max_mask,
-            max_time,
-            max_v_idx,
-        }
+    tokens.expect("Valve")?;
+    let valve = tokens.word("a valve name")?;
+    for word in ["has", "flow", "rate", "="] {
+        tokens.expect(word)?;
+    }
+    let flow_src = tokens.word("a flow rate")?;
+    let flow: i64 = line.parse(flow_src)?;
+    if flow < 0 {
+        return Err(line.error(flow_src, "the flow rate can't be negative"));
+    }
+    tokens.expect(";")?;
+
+    // "tunnel leads to valve" when there is just one
+    tokens.expect_one_of(&["tunnels", "tunnel"])?;
+    tokens.expect_one_of(&["lead", "leads"])?;
+    tokens.expect("to")?;
+    tokens.expect_one_of(&["valves", "valve"])?;
+
+    let mut tunnels = vec![tokens.word("a valve name")?];
+    while tokens.peek().is_some() {
+        tokens.expect(",")?;
+        tunnels.push(tokens.word("a valve name")?);
     }
 
-    fn get_idx(&self, src_idx: usize, dst_mask: u64, time_remaining: i64) -> usize {
-        let time_remaining = time_remaining as usize - 1;
-        let dst_mask = dst_mask as usize & (self.max_mask - 1);
-
-        let mut idx = 0;
-        idx = idx * (self.max_v_idx + 1) + src_idx.clamp(0, self.max_v_idx);
-        idx = idx * self.max_time + time_remaining;
-        idx = idx * self.max_mask + dst_mask;
+    Ok((valve, flow, tunnels))
+}
 
-        idx
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+    #[test]
+    fn example() {
+        let v = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(v.g.valves.len(), 6);
+        assert_eq!(Solver::part1(&v).unwrap(), 1651.into());
+        assert_eq!(Solver::part2(&v).unwrap(), 1707.into());
     }
 
-    fn get(&self, src_idx: usize, dst_mask: u64, time_remaining: i64) -> Option<i64> {
-        let idx = self.get_idx(src_idx, dst_mask, time_remaining);
-        if self.data[idx] < 0 {
-            None
-        } else {
-            Some(self.data[idx])
-        }
+    #[test]
+    fn settings() {
+        let mut v = Solver::parse(EXAMPLE).unwrap();
+        let mut params = Params::new();
+        params.set("start", "JJ");
+        params.set("minutes", "5");
+        params.set("elephant-minutes", "30");
+        Solver::configure(&mut v, &params).unwrap();
+
+        // JJ right away, nothing else is close enough to open in time
+        assert_eq!(Solver::part1(&v).unwrap(), (21 * 4).into());
+        let plan = v.plan(Stage::Hard).unwrap();
+        assert!(plan.openings.iter().all(|o| o.minute <= 30));
+        assert_eq!(plan.openings.len(), 6);
+
+        let mut wrong = Params::new();
+        wrong.set("minute", "5");
+        let err = Solver::configure(&mut v, &wrong).unwrap_err();
+        assert_eq!(
+            err.message,
+            "unknown parameter 'minute', expected one of start, minutes, elephant-minutes, actors"
+        );
+
+        wrong = Params::new();
+        wrong.set("actors", "0");
+        assert!(Solver::configure(&mut v, &wrong).is_err());
+
+        // alone for as long as in the first stage
+        let mut v = Solver::parse(EXAMPLE).unwrap();
+        let mut params = Params::new();
+        params.set("actors", "1");
+        params.set("elephant-minutes", "30");
+        Solver::configure(&mut v, &params).unwrap();
+        assert_eq!(Solver::part2(&v).unwrap(), 1651.into());
+
+        wrong = Params::new();
+        wrong.set("minutes", "five");
+        assert!(Solver::configure(&mut v, &wrong).is_err());
+
+        wrong = Params::new();
+        wrong.set("start", "ZZ");
+        Solver::configure(&mut v, &wrong).unwrap();
+        assert!(Solver::part1(&v).is_err());
     }
 
-    fn set(&mut self, src_idx: usize, dst_mask: u64, time_remaining: i64, value: i64) {
-        let idx = self.get_idx(src_idx, dst_mask, time_remaining);
-        self.data[idx] = value;
+    #[test]
+    fn plans() {
+        let v = Solver::parse(EXAMPLE).unwrap();
+        let g = &v.g;
+
+        // the same as in the puzzle description
+        assert_eq!(
+            Solver::explain(&v, Stage::Easy).unwrap().unwrap(),
+            "\
+Minute 2: You open valve DD (20/min, 560 in total)
+Minute 5: You open valve BB (13/min, 325 in total)
+Minute 9: You open valve JJ (21/min, 441 in total)
+Minute 17: You open valve HH (22/min, 286 in total)
+Minute 21: You open valve EE (3/min, 27 in total)
+Minute 24: You open valve CC (2/min, 12 in total)
+Released: 1651
+"
+        );
+
+        let plan = best_plan(g, START, 26, 2).unwrap();
+        assert_eq!(plan.released(), 1707);
+        let mut by_actor: Vec<Vec<&str>> = (0..2)
+            .map(|actor| {
+                let mut openings: Vec<_> =
+                    plan.openings.iter().filter(|o| o.actor == actor).collect();
+                openings.sort_by_key(|o| o.minute);
+                openings.iter().map(|o| g.names[o.valve].as_str()).collect()
+            })
+            .collect();
+        by_actor.sort();
+        assert_eq!(by_actor, [["DD", "HH", "EE"], ["JJ", "BB", "CC"]]);
     }
-}
 
-fn read_graph(input: &str) -> Graph {
-    let mut flow_rates = Vec::new();
-    let mut edges = Vec::new();
+    #[test]
+    fn split_exploration() {
+        let g = read_graph(EXAMPLE).unwrap();
+        let start = g.index_of(START).unwrap();
+
+        let mut alone = Explorer::new(&g, (0, 1));
+        alone.explore(start, 26, 0, None);
+        let released: AHashMap<ValveSet, i64> = alone
+            .best
+            .iter()
+            .map(|(valves, &(r, _))| (valves.clone(), r))
+            .collect();
+
+        // however the first moves are shared, together they find the same
+        for count in [2, 3, 7] {
+            let mut best: AHashMap<ValveSet, i64> = AHashMap::new();
+            for n in 0..count {
+                let mut explorer = Explorer::new(&g, (n, count));
+                explorer.explore(start, 26, 0, None);
+                for (valves, (r, _)) in explorer.best {
+                    let b = best.entry(valves).or_insert(r);
+                    *b = r.max(*b);
+                }
+            }
+            assert_eq!(best, released);
+        }
 
-    for line in input.lines() {
-        let mut split = line.split(';');
-        let (vert, tunnels) = (split.next().unwrap(), split.next().unwrap());
+        let subsets = best_by_subset(&g, start, 26);
+        assert_eq!(subsets.released, released);
+        for valves in released.keys() {
+            let order = alone.order(valves, released[valves], 26).unwrap();
+            assert_eq!(subsets.order(valves, 26), order);
+            let count = valves.words.iter().map(|w| w.count_ones() as usize).sum();
+            assert_eq!(order.len(), count);
+            assert_eq!(
+                order.iter().map(|&(_, _, r)| r).sum::<i64>(),
+                released[valves]
+            );
+        }
+    }
 
-        let vert_id = &vert[6..8];
-        let flow: i64 = vert[23..].parse().unwrap();
+    #[test]
+    fn more_valves_and_actors() {
+        // a corridor of 70 valves releasing 1 each, A0 to G9, too many for a 64 bit mask
+        let name = |i: i32| format!("{}{}", (b'A' + (i / 10) as u8) as char, i % 10);
+        let input: Vec<String> = (0..70)
+            .map(|i| {
+                let tunnels: Vec<String> = [i - 1, i + 1]
+                    .into_iter()
+                    .filter(|j| (0..70).contains(j))
+                    .map(name)
+                    .collect();
+                format!(
+                    "Valve {} has flow rate=1; tunnels lead to valves {}",
+                    name(i),
+                    tunnels.join(", ")
+                )
+            })
+            .collect();
+        let g = read_graph(&input.join("\n")).unwrap();
+
+        // open G8 right away, then one of its neighbours
+        assert_eq!(best_plan(&g, "G8", 5, 1).unwrap().released(), 4 + 2);
+        // the other actor takes the other side
+        let plan = best_plan(&g, "G8", 5, 2).unwrap();
+        assert_eq!(plan.released(), 4 + 2 + 3 + 1);
+        assert!(plan.openings.iter().any(|o| g.names[o.valve] == "G9"));
+        // a third one has to walk further for what is left
+        assert_eq!(best_plan(&g, "G8", 5, 3).unwrap().released(), 11);
+
+        assert!(best_plan(&g, "AA", 5, 1).is_err());
+    }
 
-        let base_tunnels_idx = if tunnels.len() % 2 == 1 { 23 } else { 24 };
-        let tunnels = tunnels[base_tunnels_idx..].split(", ").collect();
+    #[test]
+    fn parse_errors() {
+        let error = |input: &str| {
+            let err = read_graph(input).err().unwrap();
+            (err.line.unwrap(), err.column.unwrap(), err.message)
+        };
 
-        flow_rates.push((vert_id, flow));
-        edges.push((vert_id, tunnels));
+        let g = read_graph("Valve  start has flow rate = 5 ;tunnel leads to valve start").unwrap();
+        assert_eq!(g.names, ["start"]);
+
+        assert_eq!(
+            error("Valve AA has flow rate=0; tunnels lead to valves BB,"),
+            (1, 53, "expected a valve name".to_string())
+        );
+        assert_eq!(
+            error("Valve AA has flow rate=0; tunnels lead to valves BB CC"),
+            (1, 53, "expected ',', found 'CC'".to_string())
+        );
+        assert_eq!(
+            error("Valve AA has flow rate=x; tunnels lead to valves AA"),
+            (
+                1,
+                24,
+                "can't parse 'x': invalid digit found in string".to_string()
+            )
+        );
+        assert_eq!(
+            error("Valve AA has flow rate=-1; tunnels lead to valves AA"),
+            (1, 24, "the flow rate can't be negative".to_string())
+        );
+        assert_eq!(
+            error("Valve AA has flow rate=0; tunnels go to valves AA"),
+            (
+                1,
+                35,
+                "expected one of 'lead', 'leads', found 'go'".to_string()
+            )
+        );
+        assert_eq!(
+            error("Valve AA has flow rate=0; tunnel leads to valve BB"),
+            (1, 49, "tunnel leads to unknown valve 'BB'".to_string())
+        );
+        assert_eq!(
+            error(
+                "Valve AA has flow rate=0; tunnel leads to valve AA\n\
+                 Valve AA has flow rate=1; tunnel leads to valve AA"
+            ),
+            (
+                2,
+                7,
+                "valve 'AA' is already described on line 1".to_string()
+            )
+        );
     }
-
-    Graph::new(flow_rates, edges)
 }
@@ -0,0 +1,161 @@
+use ahash::AHashMap;
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    // jet directions, -1 for '<' and 1 for '>'
+    type Parsed<'a> = Vec<i8>;
+
+    fn parse(input: &str) -> Result<Vec<i8>, SolveError> {
+        let pattern = input.trim_end();
+        let jets = pattern
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c {
+                '<' => Ok(-1),
+                '>' => Ok(1),
+                c => Err(SolveError::at(
+                    1,
+                    i + 1,
+                    format_args!("unexpected jet '{c}'"),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if jets.is_empty() {
+            return Err(SolveError::new("the jet pattern is empty"));
+        }
+
+        Ok(jets)
+    }
+
+    fn part1(jets: &Vec<i8>) -> Result<Answer, SolveError> {
+        Ok(tower_height(jets, 2022).into())
+    }
+
+    fn part2(jets: &Vec<i8>) -> Result<Answer, SolveError> {
+        Ok(tower_height(jets, 1_000_000_000_000).into())
+    }
+}
+
+const WIDTH: usize = 7;
+
+// rows of each rock bottom up, bit 0 is the leftmost column of the chamber
+const ROCKS: [&[u8]; 5] = [
+    &[0b0111100],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0010000, 0b0010000],
+    &[0b0000100, 0b0000100, 0b0000100, 0b0000100],
+    &[0b0001100, 0b0001100],
+];
+
+// how many rows from the top are compared to tell whether the tower repeats itself
+const PROFILE_DEPTH: usize = 32;
+
+fn tower_height(jets: &[i8], rock_count: u64) -> u64 {
+    let mut chamber = Chamber::default();
+    let mut seen: AHashMap<(usize, usize, [u8; PROFILE_DEPTH]), (u64, usize)> = AHashMap::new();
+    let mut skipped_height = 0;
+
+    let mut rock = 0;
+    while rock < rock_count {
+        chamber.drop_rock(ROCKS[(rock % ROCKS.len() as u64) as usize], jets);
+        rock += 1;
+
+        if skipped_height != 0 || chamber.rows.len() < PROFILE_DEPTH {
+            continue;
+        }
+
+        let key = (
+            (rock % ROCKS.len() as u64) as usize,
+            chamber.jet_idx,
+            chamber.profile(),
+        );
+        if let Some(&(prev_rock, prev_height)) = seen.get(&key) {
+            let period = rock - prev_rock;
+            let cycles = (rock_count - rock) / period;
+
+            skipped_height = cycles * (chamber.rows.len() - prev_height) as u64;
+            rock += cycles * period;
+        } else {
+            seen.insert(key, (rock, chamber.rows.len()));
+        }
+    }
+
+    chamber.rows.len() as u64 + skipped_height
+}
+
+#[derive(Default)]
+struct Chamber {
+    rows: Vec<u8>,
+    jet_idx: usize,
+}
+
+impl Chamber {
+    fn drop_rock(&mut self, rock: &[u8], jets: &[i8]) {
+        let mut rock = rock.to_vec();
+        let mut bottom = self.rows.len() + 3;
+
+        loop {
+            let jet = jets[self.jet_idx];
+            self.jet_idx = (self.jet_idx + 1) % jets.len();
+
+            let pushed: Vec<u8> = match jet {
+                -1 if rock.iter().all(|r| r & 1 == 0) => rock.iter().map(|r| r >> 1).collect(),
+                1 if rock.iter().all(|r| r & (1 << (WIDTH - 1)) == 0) => {
+                    rock.iter().map(|r| r << 1).collect()
+                }
+                _ => rock.clone(),
+            };
+            if !self.collides(&pushed, bottom) {
+                rock = pushed;
+            }
+
+            if bottom == 0 || self.collides(&rock, bottom - 1) {
+                break;
+            }
+            bottom -= 1;
+        }
+
+        for (i, row) in rock.iter().enumerate() {
+            if bottom + i == self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[bottom + i] |= row;
+        }
+    }
+
+    fn collides(&self, rock: &[u8], bottom: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .any(|(i, row)| self.rows.get(bottom + i).is_some_and(|r| r & row != 0))
+    }
+
+    fn profile(&self) -> [u8; PROFILE_DEPTH] {
+        let mut result = [0; PROFILE_DEPTH];
+        result.copy_from_slice(&self.rows[self.rows.len() - PROFILE_DEPTH..]);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    #[test]
+    fn example() {
+        let jets = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(tower_height(&jets, 1), 1);
+        assert_eq!(tower_height(&jets, 10), 17);
+        assert_eq!(tower_height(&jets, 2022), 3068);
+        assert_eq!(tower_height(&jets, 1_000_000_000_000), 1514285714288);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Droplet;
+
+    fn parse(input: &str) -> Result<Droplet, SolveError> {
+        let mut cubes = Vec::new();
+
+        for row in lines(input) {
+            let (x, rest) = row.split_once(row.text, ",")?;
+            let (y, z) = row.split_once(rest, ",")?;
+            cubes.push([row.parse(x)?, row.parse(y)?, row.parse(z)?]);
+        }
+
+        if cubes.is_empty() {
+            return Err(SolveError::new("the droplet has no cubes"));
+        }
+
+        Ok(Droplet::new(&cubes))
+    }
+
+    fn part1(droplet: &Droplet) -> Result<Answer, SolveError> {
+        Ok(droplet.surface_area(false).into())
+    }
+
+    fn part2(droplet: &Droplet) -> Result<Answer, SolveError> {
+        Ok(droplet.surface_area(true).into())
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Cell {
+    Lava,
+    // trapped inside the droplet
+    Air,
+    // reachable from the outside
+    Steam,
+}
+
+/// The scan of the droplet padded with one layer of steam on every side.
+pub struct Droplet {
+    size: [usize; 3],
+    cells: Vec<Cell>,
+    cubes: Vec<[usize; 3]>,
+}
+
+const NEIGHBOURS: [[isize; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+impl Droplet {
+    fn new(cubes: &[[i32; 3]]) -> Droplet {
+        let min: [i32; 3] = std::array::from_fn(|i| cubes.iter().map(|c| c[i]).min().unwrap());
+        let max: [i32; 3] = std::array::from_fn(|i| cubes.iter().map(|c| c[i]).max().unwrap());
+        let size: [usize; 3] = std::array::from_fn(|i| (max[i] - min[i]) as usize + 3);
+
+        let cubes: Vec<[usize; 3]> = cubes
+            .iter()
+            .map(|c| std::array::from_fn(|i| (c[i] - min[i]) as usize + 1))
+            .collect();
+
+        let mut droplet = Droplet {
+            size,
+            cells: vec![Cell::Air; size.iter().product()],
+            cubes,
+        };
+        for i in 0..droplet.cubes.len() {
+            let idx = droplet.index(droplet.cubes[i]);
+            droplet.cells[idx] = Cell::Lava;
+        }
+        droplet.fill_steam();
+
+        droplet
+    }
+
+    fn fill_steam(&mut self) {
+        let mut stack = vec![[0, 0, 0]];
+        let idx = self.index([0, 0, 0]);
+        self.cells[idx] = Cell::Steam;
+
+        while let Some(pos) = stack.pop() {
+            for n in self.neighbours(pos) {
+                let idx = self.index(n);
+                if self.cells[idx] == Cell::Air {
+                    self.cells[idx] = Cell::Steam;
+                    stack.push(n);
+                }
+            }
+        }
+    }
+
+    /// Number of cube faces not touching another cube, or only those the steam can reach.
+    fn surface_area(&self, exterior_only: bool) -> usize {
+        self.cubes
+            .iter()
+            .flat_map(|&c| self.neighbours(c))
+            .filter(|&n| match self.cells[self.index(n)] {
+                Cell::Lava => false,
+                Cell::Air => !exterior_only,
+                Cell::Steam => true,
+            })
+            .count()
+    }
+
+    fn neighbours(&self, pos: [usize; 3]) -> impl Iterator<Item = [usize; 3]> {
+        let size = self.size;
+        NEIGHBOURS.iter().filter_map(move |d| {
+            let mut result = [0; 3];
+            for i in 0..3 {
+                result[i] = pos[i].checked_add_signed(d[i]).filter(|&v| v < size[i])?;
+            }
+            Some(result)
+        })
+    }
+
+    fn index(&self, pos: [usize; 3]) -> usize {
+        (pos[0] * self.size[1] + pos[1]) * self.size[2] + pos[2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+    #[test]
+    fn example() {
+        let droplet = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(droplet.surface_area(false), 64);
+        assert_eq!(droplet.surface_area(true), 58);
+        assert_eq!(
+            Solver::parse("1,1,1\n2,1,1").unwrap().surface_area(false),
+            10
+        );
+    }
+}
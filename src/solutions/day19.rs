@@ -0,0 +1,199 @@
+use std::cmp::max;
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Blueprint>;
+
+    fn parse(input: &str) -> Result<Vec<Blueprint>, SolveError> {
+        lines(input)
+            .filter(|l| !l.is_empty())
+            .map(Blueprint::parse)
+            .collect()
+    }
+
+    fn part1(blueprints: &Vec<Blueprint>) -> Result<Answer, SolveError> {
+        let result: u32 = blueprints.iter().map(|b| b.id * max_geodes(b, 24)).sum();
+
+        Ok(result.into())
+    }
+
+    fn part2(blueprints: &Vec<Blueprint>) -> Result<Answer, SolveError> {
+        let result: u32 = blueprints
+            .iter()
+            .take(3)
+            .map(|b| max_geodes(b, 32))
+            .product();
+
+        Ok(result.into())
+    }
+}
+
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+pub struct Blueprint {
+    id: u32,
+    // costs[robot][resource], geodes are never spent
+    costs: [[u32; 3]; 4],
+}
+
+impl Blueprint {
+    fn parse(line: Line) -> Result<Blueprint, SolveError> {
+        let rest = line.strip_prefix(line.text, "Blueprint ")?;
+        let (id, rest) = line.split_once(rest, ": ")?;
+
+        let rest = line.strip_prefix(rest, "Each ore robot costs ")?;
+        let (ore_robot, rest) = line.split_once(rest, " ore. ")?;
+        let rest = line.strip_prefix(rest, "Each clay robot costs ")?;
+        let (clay_robot, rest) = line.split_once(rest, " ore. ")?;
+        let rest = line.strip_prefix(rest, "Each obsidian robot costs ")?;
+        let (obsidian_robot_ore, rest) = line.split_once(rest, " ore and ")?;
+        let (obsidian_robot_clay, rest) = line.split_once(rest, " clay. ")?;
+        let rest = line.strip_prefix(rest, "Each geode robot costs ")?;
+        let (geode_robot_ore, rest) = line.split_once(rest, " ore and ")?;
+        let geode_robot_obsidian = rest
+            .strip_suffix(" obsidian.")
+            .ok_or_else(|| line.error(line.end(), "expected 'obsidian.'"))?;
+
+        Ok(Blueprint {
+            id: line.parse(id)?,
+            costs: [
+                [line.parse(ore_robot)?, 0, 0],
+                [line.parse(clay_robot)?, 0, 0],
+                [
+                    line.parse(obsidian_robot_ore)?,
+                    line.parse(obsidian_robot_clay)?,
+                    0,
+                ],
+                [
+                    line.parse(geode_robot_ore)?,
+                    0,
+                    line.parse(geode_robot_obsidian)?,
+                ],
+            ],
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+struct State {
+    time_left: u32,
+    robots: [u32; 3],
+    stock: [u32; 3],
+    // geodes the geode robots built so far will have cracked by the end
+    geodes: u32,
+}
+
+fn max_geodes(blueprint: &Blueprint, time: u32) -> u32 {
+    // there's no point in producing more of a resource per minute than can be spent
+    let max_robots: [u32; 3] =
+        std::array::from_fn(|r| blueprint.costs.iter().map(|c| c[r]).max().unwrap());
+
+    let mut best = 0;
+    search(
+        blueprint,
+        &max_robots,
+        State {
+            time_left: time,
+            robots: [1, 0, 0],
+            stock: [0; 3],
+            geodes: 0,
+        },
+        &mut best,
+    );
+
+    best
+}
+
+fn search(blueprint: &Blueprint, max_robots: &[u32; 3], state: State, best: &mut u32) {
+    *best = max(*best, state.geodes);
+    if upper_bound(blueprint, &state) <= *best {
+        return;
+    }
+
+    for robot in [GEODE, OBSIDIAN, CLAY, ORE] {
+        if robot != GEODE && state.robots[robot] >= max_robots[robot] {
+            continue;
+        }
+
+        // rather than stepping minute by minute, wait until the robot can be built
+        let cost = &blueprint.costs[robot];
+        let mut wait = 0;
+        let mut affordable = true;
+        for r in [ORE, CLAY, OBSIDIAN] {
+            if cost[r] <= state.stock[r] {
+                continue;
+            }
+            if state.robots[r] == 0 {
+                affordable = false;
+                break;
+            }
+            wait = max(wait, (cost[r] - state.stock[r]).div_ceil(state.robots[r]));
+        }
+        if !affordable || wait + 1 >= state.time_left {
+            continue;
+        }
+
+        let time_left = state.time_left - wait - 1;
+        let mut next = State {
+            time_left,
+            stock: std::array::from_fn(|r| state.stock[r] + state.robots[r] * (wait + 1) - cost[r]),
+            ..state
+        };
+        if robot == GEODE {
+            next.geodes += time_left;
+        } else {
+            next.robots[robot] += 1;
+        }
+
+        search(blueprint, max_robots, next, best);
+    }
+}
+
+// Geodes cracked if ore and clay were free and an obsidian robot could be built every minute
+// alongside anything else.
+fn upper_bound(blueprint: &Blueprint, state: &State) -> u32 {
+    let geode_cost = blueprint.costs[GEODE][OBSIDIAN];
+    let mut obsidian = state.stock[OBSIDIAN];
+    let mut result = state.geodes;
+
+    let robots = state.robots[OBSIDIAN]..;
+    for (obsidian_robots, time_left) in robots.zip((0..state.time_left).rev()) {
+        let build_geode_robot = obsidian >= geode_cost;
+        obsidian += obsidian_robots;
+        if build_geode_robot {
+            obsidian -= geode_cost;
+            result += time_left;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+
+    #[test]
+    fn example() {
+        let blueprints = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(max_geodes(&blueprints[0], 24), 9);
+        assert_eq!(max_geodes(&blueprints[1], 24), 12);
+        assert_eq!(max_geodes(&blueprints[0], 32), 56);
+        assert_eq!(max_geodes(&blueprints[1], 32), 62);
+        assert_eq!(Solver::part1(&blueprints).unwrap(), 33.into());
+    }
+}
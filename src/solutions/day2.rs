@@ -1,19 +1,29 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
 use crate::solutions::day2::MoveType::{Paper, Rock, Scissors};
-use crate::Stage;
+use crate::solutions::Solution;
 use anyhow::anyhow;
 use std::str::FromStr;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let mut rounds = Vec::from_iter(input.lines().map(|v| v.parse::<Round>().unwrap()));
+pub struct Solver;
 
-    if stage.is_hard() {
-        for round in &mut rounds {
-            round.fix_my_move()
-        }
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Round>;
+
+    fn parse(input: &str) -> Result<Vec<Round>, SolveError> {
+        lines(input).map(Round::parse).collect()
     }
 
-    let result: i64 = rounds.iter().map(|x| x.get_full_score()).sum();
-    result.to_string()
+    fn part1(rounds: &Vec<Round>) -> Result<Answer, SolveError> {
+        let result: i64 = rounds.iter().map(|x| x.get_full_score()).sum();
+        Ok(result.into())
+    }
+
+    fn part2(rounds: &Vec<Round>) -> Result<Answer, SolveError> {
+        let result: i64 = rounds.iter().map(|x| x.fixed().get_full_score()).sum();
+        Ok(result.into())
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -23,7 +33,8 @@ enum MoveType {
     Scissors = 2,
 }
 
-struct Round {
+#[derive(Copy, Clone)]
+pub struct Round {
     opponent_move: MoveType,
     my_move: MoveType,
 }
@@ -49,14 +60,18 @@ impl Round {
         self.get_round_score() + self.my_move.get_score()
     }
 
-    fn fix_my_move(&mut self) {
+    // the second column turns out to be the outcome, not my move
+    fn fixed(&self) -> Round {
         let scores = [
             [Scissors, Rock, Paper],
             [Rock, Paper, Scissors],
             [Paper, Scissors, Rock],
         ];
 
-        self.my_move = scores[self.opponent_move as usize][self.my_move as usize]
+        Round {
+            my_move: scores[self.opponent_move as usize][self.my_move as usize],
+            ..*self
+        }
     }
 }
 
@@ -73,19 +88,37 @@ impl FromStr for MoveType {
     }
 }
 
-impl FromStr for Round {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = Vec::from_iter(s.split(' '));
+impl Round {
+    fn parse(line: Line) -> Result<Round, SolveError> {
+        let parts = Vec::from_iter(line.text.split(' '));
 
         if parts.len() != 2 {
-            return Err(anyhow!("Couldn't parse round"));
+            return Err(line.error(line.text, "expected two moves separated by a space"));
         }
 
         Ok(Round {
-            opponent_move: parts[0].parse()?,
-            my_move: parts[1].parse()?,
+            opponent_move: line.parse(parts[0])?,
+            my_move: line.parse(parts[1])?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "A Y\nB X\nC Z";
+
+    #[test]
+    fn example() {
+        let rounds = Solver::parse(EXAMPLE).unwrap();
+
+        let scores: Vec<i64> = rounds.iter().map(|r| r.get_full_score()).collect();
+        assert_eq!(scores, [8, 1, 6]);
+        let scores: Vec<i64> = rounds.iter().map(|r| r.fixed().get_full_score()).collect();
+        assert_eq!(scores, [4, 1, 7]);
+
+        assert_eq!(Solver::part1(&rounds).unwrap(), 15.into());
+        assert_eq!(Solver::part2(&rounds).unwrap(), 12.into());
+    }
+}
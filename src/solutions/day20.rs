@@ -0,0 +1,108 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, SolveError> {
+        let numbers = lines(input)
+            .map(|line| line.parse(line.text))
+            .collect::<Result<Vec<i64>, _>>()?;
+
+        match numbers.iter().filter(|&&n| n == 0).count() {
+            1 => Ok(numbers),
+            0 => Err(SolveError::new(
+                "the file has no 0 to count the grove coordinates from",
+            )),
+            _ => Err(SolveError::new("the file has more than one 0")),
+        }
+    }
+
+    fn part1(numbers: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(Answer::Number(grove_coordinates(&mix(numbers, 1))))
+    }
+
+    fn part2(numbers: &Vec<i64>) -> Result<Answer, SolveError> {
+        let numbers = numbers
+            .iter()
+            .map(|&n| {
+                n.checked_mul(DECRYPTION_KEY)
+                    .ok_or_else(|| SolveError::new(format!("decrypting {n} overflows")))
+            })
+            .collect::<Result<Vec<i64>, _>>()?;
+
+        Ok(Answer::Number(grove_coordinates(&mix(&numbers, 10))))
+    }
+}
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    // order[i] is the original index of the number at position i
+    let mut order: Vec<usize> = (0..numbers.len()).collect();
+    let cycle = numbers.len() as i64 - 1;
+
+    for _ in 0..rounds {
+        for (idx, &n) in numbers.iter().enumerate() {
+            let pos = order.iter().position(|&i| i == idx).unwrap();
+            order.remove(pos);
+
+            // a full lap brings the number back where it was, so only the remainder matters
+            let shift = n.rem_euclid(cycle.max(1));
+            let new_pos = (pos as i64 + shift).rem_euclid(cycle.max(1)) as usize;
+            order.insert(new_pos, idx);
+        }
+    }
+
+    order.iter().map(|&i| numbers[i]).collect()
+}
+
+fn grove_coordinates(mixed: &[i64]) -> i128 {
+    let zero = mixed.iter().position(|&n| n == 0).unwrap();
+
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| i128::from(mixed[(zero + offset) % mixed.len()]))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1\n2\n-3\n3\n-2\n0\n4";
+
+    // the example lists the file starting with 1, positions in a circle are relative
+    fn rotated_to_one(mut mixed: Vec<i64>) -> Vec<i64> {
+        let one = mixed.iter().position(|&n| n == 1).unwrap();
+        mixed.rotate_left(one);
+        mixed
+    }
+
+    #[test]
+    fn example() {
+        let numbers = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(rotated_to_one(mix(&numbers, 1)), [1, 2, -3, 4, 0, 3, -2]);
+        assert_eq!(Solver::part1(&numbers).unwrap(), 3.into());
+        assert_eq!(Solver::part2(&numbers).unwrap(), 1623178306i64.into());
+    }
+
+    #[test]
+    fn huge_numbers() {
+        let numbers = Solver::parse("0\n20000000000\n1").unwrap();
+        assert_eq!(Solver::part1(&numbers).unwrap(), 20000000001i64.into());
+        let err = Solver::part2(&numbers).unwrap_err();
+        assert_eq!(err.message, "decrypting 20000000000 overflows");
+
+        let numbers = Solver::parse(&format!("0\n{}\n{}", i64::MAX, i64::MAX)).unwrap();
+        assert_eq!(
+            Solver::part1(&numbers).unwrap(),
+            Answer::Number(2 * i128::from(i64::MAX))
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use ahash::AHashMap;
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Troop;
+
+    fn parse(input: &str) -> Result<Troop, SolveError> {
+        Troop::parse(input)
+    }
+
+    fn part1(troop: &Troop) -> Result<Answer, SolveError> {
+        Ok(troop.eval(troop.root)?.into())
+    }
+
+    fn part2(troop: &Troop) -> Result<Answer, SolveError> {
+        let Job::Op(a, _, b) = troop.jobs[troop.root] else {
+            return Err(SolveError::new("root should compare two monkeys"));
+        };
+
+        let result = match (troop.depends_on_human(a), troop.depends_on_human(b)) {
+            (true, false) => troop.solve_for_human(a, troop.eval(b)?),
+            (false, true) => troop.solve_for_human(b, troop.eval(a)?),
+            _ => Err(SolveError::new(
+                "exactly one side of root should depend on the human",
+            )),
+        };
+
+        Ok(result?.into())
+    }
+}
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Copy, Clone)]
+enum Job {
+    Number(i64),
+    Op(usize, Operation, usize),
+}
+
+pub struct Troop {
+    jobs: Vec<Job>,
+    root: usize,
+    human: usize,
+}
+
+impl Troop {
+    fn parse(input: &str) -> Result<Troop, SolveError> {
+        let mut ids: AHashMap<&str, usize> = AHashMap::new();
+        let mut jobs_src = Vec::new();
+
+        for row in lines(input) {
+            let (name, job) = row.split_once(row.text, ": ")?;
+            if ids.insert(name, jobs_src.len()).is_some() {
+                return Err(row.error(name, format_args!("monkey '{name}' is defined twice")));
+            }
+            jobs_src.push((row, job));
+        }
+
+        let id_of = |name: &str| ids.get(name).copied();
+        let mut jobs = Vec::with_capacity(jobs_src.len());
+
+        for (row, job) in jobs_src {
+            let parts: Vec<&str> = job.split(' ').collect();
+            jobs.push(match parts[..] {
+                [number] => Job::Number(row.parse(number)?),
+                [a, op, b] => {
+                    let op = match op {
+                        "+" => Operation::Add,
+                        "-" => Operation::Sub,
+                        "*" => Operation::Mul,
+                        "/" => Operation::Div,
+                        _ => return Err(row.error(op, format_args!("unknown operation '{op}'"))),
+                    };
+                    let monkey = |name| {
+                        id_of(name).ok_or_else(|| {
+                            row.error(name, format_args!("no monkey named '{name}'"))
+                        })
+                    };
+
+                    Job::Op(monkey(a)?, op, monkey(b)?)
+                }
+                _ => return Err(row.error(job, "expected a number or an operation")),
+            });
+        }
+
+        let troop = Troop {
+            jobs,
+            root: id_of(ROOT).ok_or_else(|| SolveError::new("there's no root monkey"))?,
+            human: id_of(HUMAN).ok_or_else(|| SolveError::new("there's no humn"))?,
+        };
+        troop.check_acyclic()?;
+
+        Ok(troop)
+    }
+
+    fn check_acyclic(&self) -> Result<(), SolveError> {
+        #[derive(Copy, Clone, Eq, PartialEq)]
+        enum Mark {
+            New,
+            InProgress,
+            Done,
+        }
+
+        fn visit(jobs: &[Job], monkey: usize, marks: &mut [Mark]) -> Result<(), SolveError> {
+            match marks[monkey] {
+                Mark::Done => return Ok(()),
+                Mark::InProgress => return Err(SolveError::new("monkeys wait for each other")),
+                Mark::New => marks[monkey] = Mark::InProgress,
+            }
+            if let Job::Op(a, _, b) = jobs[monkey] {
+                visit(jobs, a, marks)?;
+                visit(jobs, b, marks)?;
+            }
+            marks[monkey] = Mark::Done;
+
+            Ok(())
+        }
+
+        let mut marks = vec![Mark::New; self.jobs.len()];
+        (0..self.jobs.len()).try_for_each(|monkey| visit(&self.jobs, monkey, &mut marks))
+    }
+
+    fn eval(&self, monkey: usize) -> Result<i64, SolveError> {
+        match self.jobs[monkey] {
+            Job::Number(n) => Ok(n),
+            Job::Op(a, op, b) => {
+                let (a, b) = (self.eval(a)?, self.eval(b)?);
+                match op {
+                    Operation::Add => a.checked_add(b),
+                    Operation::Sub => a.checked_sub(b),
+                    Operation::Mul => a.checked_mul(b),
+                    Operation::Div => a.checked_div(b),
+                }
+                .ok_or_else(|| SolveError::new(format_args!("can't compute {a} {op:?} {b}")))
+            }
+        }
+    }
+
+    fn depends_on_human(&self, monkey: usize) -> bool {
+        monkey == self.human
+            || match self.jobs[monkey] {
+                Job::Number(_) => false,
+                Job::Op(a, _, b) => self.depends_on_human(a) || self.depends_on_human(b),
+            }
+    }
+
+    /// The number the human should yell for `monkey` to yell `target`, undoing the operations
+    /// on the way down to the human one by one.
+    fn solve_for_human(&self, mut monkey: usize, mut target: i64) -> Result<i64, SolveError> {
+        let no_solution = || SolveError::new("no integer number makes both sides of root equal");
+
+        while monkey != self.human {
+            let Job::Op(a, op, b) = self.jobs[monkey] else {
+                unreachable!("only operations depend on the human")
+            };
+            let human_left = self.depends_on_human(a);
+            let other = self.eval(if human_left { b } else { a })?;
+
+            target = match (op, human_left) {
+                (Operation::Add, _) => target.checked_sub(other),
+                (Operation::Sub, true) => target.checked_add(other),
+                (Operation::Sub, false) => other.checked_sub(target),
+                (Operation::Mul, _) => exact_div(target, other),
+                (Operation::Div, true) => target.checked_mul(other),
+                (Operation::Div, false) => exact_div(other, target),
+            }
+            .ok_or_else(no_solution)?;
+            monkey = if human_left { a } else { b };
+        }
+
+        Ok(target)
+    }
+}
+
+fn exact_div(a: i64, b: i64) -> Option<i64> {
+    (a.checked_rem(b)? == 0).then(|| a.checked_div(b)).flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
+    #[test]
+    fn example() {
+        let troop = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Solver::part1(&troop).unwrap(), 152.into());
+        assert_eq!(Solver::part2(&troop).unwrap(), 301.into());
+    }
+
+    #[test]
+    fn division_overflow() {
+        assert_eq!(exact_div(i64::MIN, -1), None);
+        assert_eq!(exact_div(7, 0), None);
+        assert_eq!(exact_div(-12, 4), Some(-3));
+        assert_eq!(exact_div(13, 4), None);
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        assert!(Solver::parse("root: humn + aaaa\naaaa: aaaa * humn\nhumn: 1").is_err());
+    }
+}
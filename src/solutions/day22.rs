@@ -0,0 +1,421 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::common::point::Direction;
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = (Board, Vec<Step>);
+
+    fn parse(input: &str) -> Result<(Board, Vec<Step>), SolveError> {
+        let rows: Vec<Line> = lines(input).take_while(|l| !l.is_empty()).collect();
+        let board = Board::parse(&rows)?;
+
+        let path = lines(input)
+            .skip(rows.len())
+            .find(|l| !l.is_empty())
+            .ok_or_else(|| SolveError::new("expected the path after the board"))?;
+
+        Ok((board, parse_path(path)?))
+    }
+
+    fn part1((board, path): &(Board, Vec<Step>)) -> Result<Answer, SolveError> {
+        Ok(board
+            .walk(path, |pos, dir| board.wrap_flat(pos, dir))
+            .into())
+    }
+
+    fn part2((board, path): &(Board, Vec<Step>)) -> Result<Answer, SolveError> {
+        let cube = Cube::fold(board)?;
+
+        Ok(board.walk(path, |pos, dir| cube.wrap(pos, dir)).into())
+    }
+}
+
+pub enum Step {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+fn parse_path(line: Line) -> Result<Vec<Step>, SolveError> {
+    let mut steps = Vec::new();
+    let mut rest = line.text;
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits > 0 {
+            steps.push(Step::Forward(line.parse(&rest[..digits])?));
+            rest = &rest[digits..];
+            continue;
+        }
+
+        steps.push(match rest.chars().next().unwrap() {
+            'L' => Step::Left,
+            'R' => Step::Right,
+            c => return Err(line.error(rest, format_args!("unexpected '{c}' in the path"))),
+        });
+        rest = &rest[1..];
+    }
+
+    Ok(steps)
+}
+
+type Pos = (usize, usize);
+
+pub struct Board {
+    tiles: Vec<Vec<u8>>,
+    width: usize,
+}
+
+impl Board {
+    fn parse(rows: &[Line]) -> Result<Board, SolveError> {
+        for row in rows {
+            if let Some((col, c)) = row.text.char_indices().find(|(_, c)| !" .#".contains(*c)) {
+                return Err(row.error(&row.text[col..], format_args!("unexpected tile '{c}'")));
+            }
+        }
+        if !rows.first().is_some_and(|r| r.text.contains('.')) {
+            return Err(SolveError::at_line(
+                1,
+                "the top row has no open tile to start from",
+            ));
+        }
+
+        let width = rows.iter().map(|r| r.text.len()).max().unwrap_or(0);
+        let tiles = rows
+            .iter()
+            .map(|r| {
+                let mut row = r.text.as_bytes().to_vec();
+                row.resize(width, b' ');
+                row
+            })
+            .collect();
+
+        Ok(Board { tiles, width })
+    }
+
+    fn height(&self) -> usize {
+        self.tiles.len()
+    }
+
+    fn tile(&self, (row, col): Pos) -> u8 {
+        self.tiles[row][col]
+    }
+
+    fn step(&self, (row, col): Pos, dir: Direction) -> Option<Pos> {
+        let d = dir.vec::<isize>();
+        let row = row.checked_add_signed(d.y).filter(|&r| r < self.height())?;
+        let col = col.checked_add_signed(d.x).filter(|&c| c < self.width)?;
+
+        (self.tile((row, col)) != b' ').then_some((row, col))
+    }
+
+    /// Follows the path and returns the final password, `wrap` tells where stepping off the
+    /// board leads.
+    fn walk(&self, path: &[Step], wrap: impl Fn(Pos, Direction) -> (Pos, Direction)) -> usize {
+        let mut pos = (0, self.tiles[0].iter().position(|&t| t == b'.').unwrap());
+        let mut dir = Direction::Right;
+
+        for step in path {
+            match step {
+                Step::Left => dir = dir.turn_left(),
+                Step::Right => dir = dir.turn_right(),
+                Step::Forward(count) => {
+                    for _ in 0..*count {
+                        let (next, next_dir) = match self.step(pos, dir) {
+                            Some(next) => (next, dir),
+                            None => wrap(pos, dir),
+                        };
+                        if self.tile(next) == b'#' {
+                            break;
+                        }
+                        (pos, dir) = (next, next_dir);
+                    }
+                }
+            }
+        }
+
+        // facings score in clockwise order starting from right
+        1000 * (pos.0 + 1) + 4 * (pos.1 + 1) + dir as usize
+    }
+
+    fn wrap_flat(&self, mut pos: Pos, dir: Direction) -> (Pos, Direction) {
+        let back = dir.reverse();
+        while let Some(prev) = self.step(pos, back) {
+            pos = prev;
+        }
+
+        (pos, dir)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct Vec3([i64; 3]);
+
+impl Vec3 {
+    fn dot(self, other: Vec3) -> i64 {
+        (0..3).map(|i| self.0[i] * other.0[i]).sum()
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        self + -other
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3(self.0.map(|v| -v))
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, k: i64) -> Vec3 {
+        Vec3(self.0.map(|v| v * k))
+    }
+}
+
+/// Where a face of the board ended up on the cube.
+///
+/// The cube spans `0..=2 * size` on every axis, so that the centre of the tile `(x, y)` of a
+/// face is at `origin + right * (2x + 1) + down * (2y + 1)`. `normal` points outwards.
+#[derive(Copy, Clone, Debug)]
+struct Face {
+    origin: Vec3,
+    right: Vec3,
+    down: Vec3,
+    normal: Vec3,
+}
+
+impl Face {
+    /// The face the cube lands on when rolled over the edge of this one that the neighbouring
+    /// block at `(dr, dc)` shares, `edge` being the length of the cube's edges.
+    fn rolled(&self, (dr, dc): (isize, isize), edge: i64) -> Face {
+        let f = self;
+        match (dr, dc) {
+            (0, 1) => Face {
+                origin: f.origin + f.right * edge,
+                right: -f.normal,
+                down: f.down,
+                normal: f.right,
+            },
+            (1, 0) => Face {
+                origin: f.origin + f.down * edge,
+                right: f.right,
+                down: -f.normal,
+                normal: f.down,
+            },
+            (0, -1) => Face {
+                origin: f.origin - f.normal * edge,
+                right: f.normal,
+                down: f.down,
+                normal: -f.right,
+            },
+            (-1, 0) => Face {
+                origin: f.origin - f.normal * edge,
+                right: f.right,
+                down: f.normal,
+                normal: -f.down,
+            },
+            _ => unreachable!("blocks only neighbour orthogonally"),
+        }
+    }
+}
+
+struct Cube {
+    size: usize,
+    // indexed by the block of the board the face occupies
+    faces: Vec<Vec<Option<Face>>>,
+}
+
+impl Cube {
+    /// Folds the board along the edges between its faces, whatever shape the net is.
+    fn fold(board: &Board) -> Result<Cube, SolveError> {
+        let tiles = board.tiles.iter().flatten().filter(|&&t| t != b' ').count();
+        let size = (1..=tiles)
+            .find(|n| 6 * n * n >= tiles)
+            .filter(|n| 6 * n * n == tiles)
+            .ok_or_else(|| SolveError::new("the board doesn't have 6 equal faces"))?;
+
+        let blocks = (board.height().div_ceil(size), board.width.div_ceil(size));
+        let is_face = |(br, bc): (usize, usize)| {
+            let tile = |r, c| {
+                board
+                    .tiles
+                    .get(r)
+                    .and_then(|row: &Vec<u8>| row.get(c).copied())
+            };
+            let (r0, c0) = (br * size, bc * size);
+            match tile(r0, c0) {
+                Some(t) if t != b' ' => Some(
+                    (r0..r0 + size)
+                        .all(|r| (c0..c0 + size).all(|c| tile(r, c).is_some_and(|t| t != b' '))),
+                ),
+                _ => None,
+            }
+        };
+
+        let start = (
+            0,
+            board.tiles[0].iter().position(|&t| t != b' ').unwrap() / size,
+        );
+        if is_face(start) != Some(true) {
+            return Err(SolveError::new("the board isn't made of square faces"));
+        }
+        let mut faces = vec![vec![None; blocks.1]; blocks.0];
+        faces[start.0][start.1] = Some(Face {
+            origin: Vec3([0, 0, 0]),
+            right: Vec3([1, 0, 0]),
+            down: Vec3([0, 1, 0]),
+            normal: Vec3([0, 0, -1]),
+        });
+
+        let edge = 2 * size as i64;
+        let mut stack = vec![start];
+        let mut face_count = 0;
+        while let Some((br, bc)) = stack.pop() {
+            face_count += 1;
+            let f = faces[br][bc].unwrap();
+
+            for (dr, dc) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                let (Some(nr), Some(nc)) = (br.checked_add_signed(dr), bc.checked_add_signed(dc))
+                else {
+                    continue;
+                };
+                if nr >= blocks.0 || nc >= blocks.1 || faces[nr][nc].is_some() {
+                    continue;
+                }
+                match is_face((nr, nc)) {
+                    None => continue,
+                    Some(false) => {
+                        return Err(SolveError::new("the board isn't made of square faces"))
+                    }
+                    Some(true) => {}
+                }
+
+                faces[nr][nc] = Some(f.rolled((dr, dc), edge));
+                stack.push((nr, nc));
+            }
+        }
+
+        let normals: Vec<Vec3> = faces.iter().flatten().flatten().map(|f| f.normal).collect();
+        if face_count != 6 || (0..6).any(|i| normals[i + 1..].contains(&normals[i])) {
+            return Err(SolveError::new("the board doesn't fold into a cube"));
+        }
+
+        Ok(Cube { size, faces })
+    }
+
+    fn face_of(&self, (row, col): Pos) -> &Face {
+        self.faces[row / self.size][col / self.size]
+            .as_ref()
+            .unwrap()
+    }
+
+    fn wrap(&self, (row, col): Pos, dir: Direction) -> (Pos, Direction) {
+        let f = self.face_of((row, col));
+        let (x, y) = ((col % self.size) as i64, (row % self.size) as i64);
+        let point = f.origin + f.right * (2 * x + 1) + f.down * (2 * y + 1);
+
+        let heading = [f.right, f.down, -f.right, -f.down][dir as usize];
+        // over the edge and down the face we were heading towards
+        let point = point + heading - f.normal;
+        let (target_normal, heading) = (heading, -f.normal);
+
+        for (br, row) in self.faces.iter().enumerate() {
+            for (bc, g) in row.iter().enumerate() {
+                let Some(g) = g.filter(|g| g.normal == target_normal) else {
+                    continue;
+                };
+                let offset = point - g.origin;
+                let x = ((offset.dot(g.right) - 1) / 2) as usize;
+                let y = ((offset.dot(g.down) - 1) / 2) as usize;
+                let dir = [g.right, g.down, -g.right, -g.down]
+                    .iter()
+                    .position(|&d| d == heading)
+                    .unwrap();
+
+                return (
+                    (br * self.size + y, bc * self.size + x),
+                    Direction::ALL[dir],
+                );
+            }
+        }
+
+        unreachable!("every direction has a face on a cube")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+
+    #[test]
+    fn example() {
+        let parsed = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Solver::part1(&parsed).unwrap(), 6032.into());
+        assert_eq!(Solver::part2(&parsed).unwrap(), 5031.into());
+    }
+
+    #[test]
+    fn walking_back_over_an_edge_returns_to_the_same_tile() {
+        let (board, _) = Solver::parse(EXAMPLE).unwrap();
+        let cube = Cube::fold(&board).unwrap();
+
+        for row in 0..board.height() {
+            for col in 0..board.width {
+                if board.tile((row, col)) == b' ' {
+                    continue;
+                }
+                for dir in Direction::ALL
+                    .into_iter()
+                    .filter(|&d| board.step((row, col), d).is_none())
+                {
+                    let (pos, new_dir) = cube.wrap((row, col), dir);
+                    assert_eq!(
+                        cube.wrap(pos, new_dir.reverse()),
+                        ((row, col), dir.reverse())
+                    );
+                }
+            }
+        }
+    }
+}
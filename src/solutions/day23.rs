@@ -0,0 +1,224 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::common::point::{Point2, Vec2};
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Pos>;
+
+    fn parse(input: &str) -> Result<Vec<Pos>, SolveError> {
+        let mut elves = Vec::new();
+
+        for row in lines(input) {
+            for (col, c) in row.text.char_indices() {
+                match c {
+                    '#' => elves.push(Pos::new(col as i32, row.no as i32 - 1)),
+                    '.' => {}
+                    c => return Err(row.error(&row.text[col..], format_args!("unexpected '{c}'"))),
+                }
+            }
+        }
+
+        Ok(elves)
+    }
+
+    fn part1(elves: &Vec<Pos>) -> Result<Answer, SolveError> {
+        let mut grove = Grove::new(elves);
+        for round in 0..10 {
+            grove.spread(round);
+        }
+
+        Ok(grove.empty_ground().into())
+    }
+
+    fn part2(elves: &Vec<Pos>) -> Result<Answer, SolveError> {
+        let mut grove = Grove::new(elves);
+        let mut round = 0;
+        while grove.spread(round) {
+            round += 1;
+        }
+
+        Ok((round + 1).into())
+    }
+}
+
+type Pos = Point2<i32>;
+
+const N: Vec2<i32> = Vec2::new(0, -1);
+const S: Vec2<i32> = Vec2::new(0, 1);
+const W: Vec2<i32> = Vec2::new(-1, 0);
+const E: Vec2<i32> = Vec2::new(1, 0);
+const NW: Vec2<i32> = Vec2::new(-1, -1);
+const NE: Vec2<i32> = Vec2::new(1, -1);
+const SW: Vec2<i32> = Vec2::new(-1, 1);
+const SE: Vec2<i32> = Vec2::new(1, 1);
+
+// the directions the elves consider, in the order of the first round, with the three cells
+// that have to be free to move there
+const MOVES: [(Vec2<i32>, [Vec2<i32>; 3]); 4] = [
+    (N, [NW, N, NE]),
+    (S, [SW, S, SE]),
+    (W, [NW, W, SW]),
+    (E, [NE, E, SE]),
+];
+
+const ADJACENT: [Vec2<i32>; 8] = [NW, N, NE, W, E, SW, S, SE];
+
+// free space kept around the elves whenever the grid has to grow
+const MARGIN: i32 = 16;
+
+/// The elves on a grid that grows as they spread out.
+struct Grove {
+    elves: Vec<Pos>,
+    // columns and rows of the grid, which starts at `origin`
+    size: Vec2<i32>,
+    origin: Pos,
+    occupied: Vec<bool>,
+    proposals: Vec<u8>,
+}
+
+impl Grove {
+    fn new(elves: &[Pos]) -> Grove {
+        let mut grove = Grove {
+            elves: elves.to_vec(),
+            size: Vec2::default(),
+            origin: Pos::default(),
+            occupied: Vec::new(),
+            proposals: Vec::new(),
+        };
+        grove.regrow();
+
+        grove
+    }
+
+    fn bounds(&self) -> (Pos, Pos) {
+        let xs = self.elves.iter().map(|e| e.x);
+        let ys = self.elves.iter().map(|e| e.y);
+
+        (
+            Pos::new(xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0)),
+            Pos::new(xs.max().unwrap_or(0), ys.max().unwrap_or(0)),
+        )
+    }
+
+    fn regrow(&mut self) {
+        let (min, max) = self.bounds();
+        let margin = Vec2::new(MARGIN, MARGIN);
+        self.origin = min - margin;
+        self.size = max - min + Vec2::new(1, 1) + margin * 2;
+
+        let cells = (self.size.x * self.size.y) as usize;
+        self.occupied = vec![false; cells];
+        self.proposals = vec![0; cells];
+        for i in 0..self.elves.len() {
+            let idx = self.index(self.elves[i]);
+            self.occupied[idx] = true;
+        }
+    }
+
+    fn index(&self, pos: Pos) -> usize {
+        let offset = pos - self.origin;
+        (offset.y * self.size.x + offset.x) as usize
+    }
+
+    fn is_free(&self, pos: Pos, d: Vec2<i32>) -> bool {
+        !self.occupied[self.index(pos + d)]
+    }
+
+    fn proposal(&self, elf: Pos, round: usize) -> Option<Pos> {
+        if ADJACENT.iter().all(|&d| self.is_free(elf, d)) {
+            return None;
+        }
+
+        (0..MOVES.len())
+            .map(|i| MOVES[(round + i) % MOVES.len()])
+            .find(|(_, checks)| checks.iter().all(|&d| self.is_free(elf, d)))
+            .map(|(d, _)| elf + d)
+    }
+
+    /// Runs a round and tells whether any elf has moved.
+    fn spread(&mut self, round: usize) -> bool {
+        let near_edge = self.elves.iter().any(|&elf| {
+            let offset = elf - self.origin;
+            offset.x <= 1
+                || offset.y <= 1
+                || offset.x + 2 >= self.size.x
+                || offset.y + 2 >= self.size.y
+        });
+        if near_edge {
+            self.regrow();
+        }
+
+        let proposals: Vec<Option<Pos>> = self
+            .elves
+            .iter()
+            .map(|&e| self.proposal(e, round))
+            .collect();
+        for &p in proposals.iter().flatten() {
+            let idx = self.index(p);
+            self.proposals[idx] += 1;
+        }
+
+        let mut moved = false;
+        for (i, p) in proposals.into_iter().enumerate() {
+            let Some(p) = p else { continue };
+            let target = self.index(p);
+            if self.proposals[target] == 1 {
+                let source = self.index(self.elves[i]);
+                self.occupied[source] = false;
+                self.occupied[target] = true;
+                self.elves[i] = p;
+                moved = true;
+            }
+            self.proposals[target] = 0;
+        }
+
+        moved
+    }
+
+    fn empty_ground(&self) -> usize {
+        let (min, max) = self.bounds();
+        let size = max - min + Vec2::new(1, 1);
+        (size.x * size.y) as usize - self.elves.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..";
+
+    #[test]
+    fn example() {
+        let elves = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Solver::part1(&elves).unwrap(), 110.into());
+        assert_eq!(Solver::part2(&elves).unwrap(), 20.into());
+    }
+
+    #[test]
+    fn small_example() {
+        let mut grove =
+            Grove::new(&Solver::parse(".....\n..##.\n..#..\n.....\n..##.\n.....").unwrap());
+        for round in 0..3 {
+            grove.spread(round);
+        }
+
+        // as (row, column)
+        let mut elves: Vec<_> = grove.elves.iter().map(|e| (e.y, e.x)).collect();
+        elves.sort();
+        assert_eq!(elves, [(0, 2), (1, 4), (2, 0), (3, 4), (5, 2)]);
+        assert!(!grove.spread(3));
+    }
+}
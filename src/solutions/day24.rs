@@ -0,0 +1,203 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Valley;
+
+    fn parse(input: &str) -> Result<Valley, SolveError> {
+        Valley::parse(&lines(input).collect::<Vec<_>>())
+    }
+
+    fn part1(valley: &Valley) -> Result<Answer, SolveError> {
+        Ok(valley.trip(Gap::Entrance, 0)?.into())
+    }
+
+    fn part2(valley: &Valley) -> Result<Answer, SolveError> {
+        // there, back for the snacks and there again
+        let there = valley.trip(Gap::Entrance, 0)?;
+        let back = valley.trip(Gap::Exit, there)?;
+
+        Ok(valley.trip(Gap::Entrance, back)?.into())
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Gap {
+    Entrance,
+    Exit,
+}
+
+/// The inside of the valley, without its walls.
+pub struct Valley {
+    width: usize,
+    height: usize,
+    // columns of the gaps in the top and bottom walls
+    entrance: usize,
+    exit: usize,
+    // blizzards by their starting position, one grid per direction: right, left, down, up
+    blizzards: [Vec<bool>; 4],
+}
+
+impl Valley {
+    fn parse(rows: &[Line]) -> Result<Valley, SolveError> {
+        let (Some(top), Some(bottom)) = (rows.first(), rows.last()) else {
+            return Err(SolveError::new("the map is empty"));
+        };
+        if rows.len() < 3 || top.text.len() < 3 {
+            return Err(SolveError::new("the valley is too small"));
+        }
+
+        let wall_gap = |row: &Line| {
+            let gaps: Vec<usize> = row.text.match_indices('.').map(|(i, _)| i).collect();
+            match gaps[..] {
+                [gap] if gap > 0 && gap < row.text.len() - 1 => Ok(gap - 1),
+                _ => Err(row.error(row.text, "expected a wall with a single gap")),
+            }
+        };
+        let entrance = wall_gap(top)?;
+        let exit = wall_gap(bottom)?;
+
+        let width = top.text.len() - 2;
+        let height = rows.len() - 2;
+        let mut blizzards: [Vec<bool>; 4] = std::array::from_fn(|_| vec![false; width * height]);
+
+        for (r, row) in rows[1..rows.len() - 1].iter().enumerate() {
+            if row.text.len() != width + 2 {
+                return Err(row.error(row.end(), "all rows of the map should have the same length"));
+            }
+            let inside = row
+                .text
+                .strip_prefix('#')
+                .and_then(|s| s.strip_suffix('#'))
+                .ok_or_else(|| row.error(row.text, "expected the valley to be walled"))?;
+
+            for (c, tile) in inside.char_indices() {
+                let kind = match tile {
+                    '>' => 0,
+                    '<' => 1,
+                    'v' => 2,
+                    '^' => 3,
+                    '.' => continue,
+                    _ => return Err(row.error(&inside[c..], format_args!("unexpected '{tile}'"))),
+                };
+                blizzards[kind][r * width + c] = true;
+            }
+        }
+
+        Ok(Valley {
+            width,
+            height,
+            entrance,
+            exit,
+            blizzards,
+        })
+    }
+
+    fn is_free(&self, row: usize, col: usize, time: usize) -> bool {
+        let (w, h) = (self.width, self.height);
+        let at = |kind: usize, r: usize, c: usize| self.blizzards[kind][r * w + c];
+
+        !(at(0, row, (col + w - time % w) % w)
+            || at(1, row, (col + time) % w)
+            || at(2, (row + h - time % h) % h, col)
+            || at(3, (row + time) % h, col))
+    }
+
+    /// The minute the expedition can reach the other gap at the earliest, leaving `from` at
+    /// minute `start`.
+    fn trip(&self, from: Gap, start: usize) -> Result<usize, SolveError> {
+        let (w, h) = (self.width, self.height);
+        let (first, last) = match from {
+            Gap::Entrance => ((0, self.entrance), (h - 1, self.exit)),
+            Gap::Exit => ((h - 1, self.exit), (0, self.entrance)),
+        };
+
+        // the blizzards are back where they started after this many minutes
+        let period = lcm(w, h);
+        let mut reachable = vec![false; w * h];
+        let mut next = vec![false; w * h];
+        // the expedition can always wait at the gap for a period, so what is reachable at some
+        // minute still is a period later, and once a whole period adds nothing, nothing ever will
+        let mut a_period_ago = vec![Vec::new(); period];
+
+        for time in start.. {
+            if reachable[last.0 * w + last.1] {
+                return Ok(time + 1);
+            }
+            let before = &mut a_period_ago[time % period];
+            if *before == reachable {
+                return Err(SolveError::new(
+                    "the blizzards never let the expedition through",
+                ));
+            }
+            before.clone_from(&reachable);
+
+            for r in 0..h {
+                for c in 0..w {
+                    let i = r * w + c;
+                    let reached = reachable[i]
+                        || (r > 0 && reachable[i - w])
+                        || (r + 1 < h && reachable[i + w])
+                        || (c > 0 && reachable[i - 1])
+                        || (c + 1 < w && reachable[i + 1])
+                        || (r, c) == first;
+                    next[i] = reached && self.is_free(r, c, time + 1);
+                }
+            }
+            std::mem::swap(&mut reachable, &mut next);
+        }
+
+        unreachable!()
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#";
+
+    #[test]
+    fn example() {
+        let valley = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(valley.trip(Gap::Entrance, 0).unwrap(), 18);
+        assert_eq!(valley.trip(Gap::Exit, 18).unwrap(), 41);
+        assert_eq!(valley.trip(Gap::Entrance, 41).unwrap(), 54);
+    }
+
+    #[test]
+    fn blocked_for_good() {
+        // a blizzard going sideways in a single column never moves out of the way
+        let valley = Solver::parse("#.#\n#.#\n#>#\n#.#\n#.#").unwrap();
+
+        let err = valley.trip(Gap::Entrance, 0).unwrap_err();
+        assert_eq!(
+            err.message,
+            "the blizzards never let the expedition through"
+        );
+        assert!(valley.trip(Gap::Exit, 5).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, SolveError> {
+        lines(input).map(from_snafu).collect()
+    }
+
+    fn part1(numbers: &Vec<i64>) -> Result<Answer, SolveError> {
+        let sum = numbers
+            .iter()
+            .try_fold(0i64, |acc, &n| acc.checked_add(n))
+            .ok_or_else(|| SolveError::new("the fuel requirements don't fit in 64 bits"))?;
+
+        Ok(to_snafu(sum).into())
+    }
+
+    fn part2(_: &Vec<i64>) -> Result<Answer, SolveError> {
+        Err(SolveError::new("there's no second puzzle on the last day"))
+    }
+}
+
+fn from_snafu(line: Line) -> Result<i64, SolveError> {
+    if line.is_empty() {
+        return Err(line.error(line.text, "expected a SNAFU number"));
+    }
+
+    line.text.char_indices().try_fold(0i64, |acc, (i, c)| {
+        let digit = match c {
+            '2' => 2,
+            '1' => 1,
+            '0' => 0,
+            '-' => -1,
+            '=' => -2,
+            c => {
+                return Err(line.error(
+                    &line.text[i..],
+                    format_args!("unexpected SNAFU digit '{c}'"),
+                ))
+            }
+        };
+
+        // a negative last digit can bring the number back in range, so only check the result
+        i64::try_from(acc as i128 * 5 + digit)
+            .map_err(|_| line.error(line.text, "the number doesn't fit in 64 bits"))
+    })
+}
+
+fn to_snafu(mut n: i64) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+
+    let mut digits = Vec::new();
+    while n != 0 {
+        // 3 and 4 are written as -2 and -1, carrying one more five
+        let (mut rest, digit) = (n.div_euclid(5), n.rem_euclid(5));
+        if digit > 2 {
+            rest += 1;
+        }
+        digits.push(b"012=-"[digit as usize] as char);
+        n = rest;
+    }
+
+    digits.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122";
+
+    #[test]
+    fn example() {
+        let numbers = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(numbers[..3], [1747, 906, 198]);
+        assert_eq!(Solver::part1(&numbers).unwrap(), Answer::text("2=-1=0"));
+    }
+
+    #[test]
+    fn round_trip() {
+        for (n, snafu) in [
+            (0, "0"),
+            (3, "1="),
+            (2022, "1=11-2"),
+            (314159265, "1121-1110-1=0"),
+            (-3, "-2"),
+        ] {
+            assert_eq!(to_snafu(n), snafu);
+            assert_eq!(from_snafu(Line { no: 1, text: snafu }).unwrap(), n);
+        }
+
+        for n in [i64::MAX, i64::MAX - 3, i64::MIN, i64::MIN + 2] {
+            let snafu = to_snafu(n);
+            assert_eq!(
+                from_snafu(Line {
+                    no: 1,
+                    text: &snafu
+                })
+                .unwrap(),
+                n
+            );
+        }
+    }
+}
@@ -1,25 +1,36 @@
-use crate::Stage;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::Solution;
 use std::collections::HashSet;
 use std::hash::RandomState;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let src = input.lines();
-
-    let result: i64 = match stage {
-        Stage::Easy => src
-            .map(|x| split_in_halves(x))
-            .map(|x| get_common_char(x.iter().map(|x| *x)))
-            .map(|c| get_priority(c))
-            .sum(),
-        Stage::Hard => src
-            .collect::<Vec<&str>>()
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<Line<'a>>;
+
+    fn parse(input: &str) -> Result<Vec<Line<'_>>, SolveError> {
+        Ok(lines(input).collect())
+    }
+
+    fn part1(rucksacks: &Vec<Line>) -> Result<Answer, SolveError> {
+        let result: i64 = rucksacks
+            .iter()
+            .map(|x| get_priority(*x, get_common_char(split_in_halves(x.text).into_iter())))
+            .sum::<Result<_, _>>()?;
+
+        Ok(result.into())
+    }
+
+    fn part2(rucksacks: &Vec<Line>) -> Result<Answer, SolveError> {
+        let result: i64 = rucksacks
             .chunks(3)
-            .map(|x| get_common_char(x.iter().map(|x| *x)))
-            .map(|c| get_priority(c))
-            .sum(),
-    };
+            .map(|x| get_priority(x[0], get_common_char(x.iter().map(|x| x.text))))
+            .sum::<Result<_, _>>()?;
 
-    result.to_string()
+        Ok(result.into())
+    }
 }
 
 fn split_in_halves(row: &str) -> [&str; 2] {
@@ -28,21 +39,52 @@ fn split_in_halves(row: &str) -> [&str; 2] {
     [&row[..halfway], &row[halfway..]]
 }
 
-fn get_common_char<'a>(strings: impl Iterator<Item = &'a str>) -> char {
+fn get_common_char<'a>(strings: impl Iterator<Item = &'a str>) -> Option<char> {
     let intersection = strings
         .map(|s| HashSet::from_iter(s.chars()))
-        .reduce(|cur: HashSet<char, RandomState>, x| cur.intersection(&x).map(|x| *x).collect())
-        .unwrap();
+        .reduce(|cur: HashSet<char, RandomState>, x| cur.intersection(&x).copied().collect())?;
 
-    intersection.iter().next().unwrap().clone()
+    intersection.iter().next().copied()
 }
 
-fn get_priority(c: char) -> i64 {
+fn get_priority(line: Line, c: Option<char>) -> Result<i64, SolveError> {
     match c {
-        'a'..='z' => c as i64 - 'a' as i64 + 1,
-        'A'..='Z' => c as i64 - 'A' as i64 + 27,
-        _ => {
-            panic!()
-        }
+        Some(c @ 'a'..='z') => Ok(c as i64 - 'a' as i64 + 1),
+        Some(c @ 'A'..='Z') => Ok(c as i64 - 'A' as i64 + 27),
+        Some(c) => Err(line.error(line.text, format_args!("invalid item type '{c}'"))),
+        None => Err(line.error(line.text, "no common item type")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    #[test]
+    fn example() {
+        let rucksacks = Solver::parse(EXAMPLE).unwrap();
+
+        let common: Vec<_> = rucksacks
+            .iter()
+            .map(|r| get_common_char(split_in_halves(r.text).into_iter()).unwrap())
+            .collect();
+        assert_eq!(common, ['p', 'L', 'P', 'v', 't', 's']);
+        let priorities: Vec<i64> = rucksacks
+            .iter()
+            .zip(common)
+            .map(|(&r, c)| get_priority(r, Some(c)).unwrap())
+            .collect();
+        assert_eq!(priorities, [16, 38, 42, 22, 20, 19]);
+
+        assert_eq!(Solver::part1(&rucksacks).unwrap(), 157.into());
+        assert_eq!(Solver::part2(&rucksacks).unwrap(), 70.into());
     }
 }
@@ -1,52 +1,91 @@
-use crate::Stage;
-use std::error::Error;
-use std::ops::RangeInclusive;
-use std::str::FromStr;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::common::MyRange;
+use crate::solutions::Solution;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let pairs = input.lines().map(|x| x.parse().unwrap());
+pub struct Solver;
 
-    let result = pairs.filter(|x: &JobPair| match stage {
-        Stage::Easy => x.is_full_overlap(),
-        Stage::Hard => x.is_any_overlap()
-    }).count();
+impl Solution for Solver {
+    type Parsed<'a> = Vec<JobPair>;
 
-    result.to_string()
+    fn parse(input: &str) -> Result<Vec<JobPair>, SolveError> {
+        lines(input).map(JobPair::parse).collect()
+    }
+
+    fn part1(pairs: &Vec<JobPair>) -> Result<Answer, SolveError> {
+        Ok(pairs.iter().filter(|x| x.is_full_overlap()).count().into())
+    }
+
+    fn part2(pairs: &Vec<JobPair>) -> Result<Answer, SolveError> {
+        Ok(pairs.iter().filter(|x| x.is_any_overlap()).count().into())
+    }
 }
 
-struct JobPair {
-    first: RangeInclusive<i32>,
-    second: RangeInclusive<i32>,
+#[derive(Debug)]
+pub struct JobPair {
+    first: MyRange<i32>,
+    second: MyRange<i32>,
 }
 
 impl JobPair {
     fn is_full_overlap(&self) -> bool {
-        self.first.contains(self.second.start()) && self.first.contains(self.second.end())
-            || self.second.contains(self.first.start()) && self.second.contains(self.first.end())
+        self.first.contains_range(&self.second) || self.second.contains_range(&self.first)
     }
 
     fn is_any_overlap(&self) -> bool {
-        self.first.contains(self.second.start())
-            || self.first.contains(self.second.end())
-            || self.second.contains(self.first.start())
-            || self.second.contains(self.first.end())
+        self.first.intersection(&self.second).is_some()
     }
-}
 
-impl FromStr for JobPair {
-    type Err = Box<dyn Error>;
+    fn parse(line: Line) -> Result<JobPair, SolveError> {
+        let (first, second) = line.split_once(line.text, ",")?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(',').collect();
-
-        let parse_range = |s: &str| -> Result<RangeInclusive<i32>, Self::Err> {
-            let parts: Vec<&str> = s.split('-').collect();
-            Ok(RangeInclusive::new(parts[0].parse()?, parts[1].parse()?))
+        let parse_range = |s: &str| -> Result<MyRange<i32>, SolveError> {
+            let (from, to) = s
+                .split_once('-')
+                .ok_or_else(|| line.error(s, "expected a range like '2-4'"))?;
+            MyRange::try_new(line.parse(from)?, line.parse(to)?)
+                .ok_or_else(|| line.error(s, "the range ends before it starts"))
         };
 
         Ok(JobPair {
-            first: parse_range(parts[0])?,
-            second: parse_range(parts[1])?,
+            first: parse_range(first)?,
+            second: parse_range(second)?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+
+    #[test]
+    fn example() {
+        let pairs = Solver::parse(EXAMPLE).unwrap();
+
+        let full: Vec<bool> = pairs.iter().map(|p| p.is_full_overlap()).collect();
+        assert_eq!(full, [false, false, false, true, true, false]);
+        let any: Vec<bool> = pairs.iter().map(|p| p.is_any_overlap()).collect();
+        assert_eq!(any, [false, false, true, true, true, true]);
+
+        assert_eq!(Solver::part1(&pairs).unwrap(), 2.into());
+        assert_eq!(Solver::part2(&pairs).unwrap(), 4.into());
+    }
+
+    #[test]
+    fn inverted_range() {
+        let err = Solver::parse("2-4,6-8\n4-2,1-3").unwrap_err();
+        assert!(
+            err.to_string().contains("the range ends before it starts"),
+            "{err}"
+        );
+    }
+}
@@ -1,37 +1,63 @@
-use std::num::ParseIntError;
-use std::str::FromStr;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::{lines, Line};
+use crate::solutions::Solution;
 
-use crate::Stage;
+pub struct Solver;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let mut stacks = CrateStacks::from_strings(input.lines().take_while(|s| s.len() > 0).collect());
+impl Solution for Solver {
+    type Parsed<'a> = (CrateStacks, Vec<MoveOp>);
 
-    let moves = input
-        .lines()
-        .skip_while(|s| s.len() > 0)
-        .skip(1)
-        .map(|s| s.parse::<MoveOp>().unwrap());
+    fn parse(input: &str) -> Result<(CrateStacks, Vec<MoveOp>), SolveError> {
+        let drawing: Vec<_> = lines(input).take_while(|s| !s.is_empty()).collect();
+        let stacks = CrateStacks::from_drawing(&drawing)?;
 
+        let moves = lines(input)
+            .skip(drawing.len() + 1)
+            .map(MoveOp::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok((stacks, moves))
+    }
+
+    fn part1((stacks, moves): &(CrateStacks, Vec<MoveOp>)) -> Result<Answer, SolveError> {
+        rearrange(stacks.clone(), moves, false)
+    }
+
+    fn part2((stacks, moves): &(CrateStacks, Vec<MoveOp>)) -> Result<Answer, SolveError> {
+        rearrange(stacks.clone(), moves, true)
+    }
+}
+
+fn rearrange(
+    mut stacks: CrateStacks,
+    moves: &[MoveOp],
+    preserve_order: bool,
+) -> Result<Answer, SolveError> {
     for m in moves {
-        stacks.execute(m, stage.is_hard());
+        stacks.execute(m, preserve_order)?;
     }
 
     let top_crates: Vec<String> = stacks
         .stacks
         .iter()
-        .map(|x| x.last().or(Some(&'\0')).unwrap().to_string())
+        .map(|x| x.last().unwrap_or(&'\0').to_string())
         .collect();
 
-    top_crates.join("")
+    Ok(top_crates.join("").into())
 }
 
-struct CrateStacks {
+#[derive(Clone)]
+pub struct CrateStacks {
     stacks: Vec<Vec<char>>,
 }
 
 impl CrateStacks {
-    fn from_strings(ss: Vec<&str>) -> CrateStacks {
-        let cnt = (ss[0].len() + 1) / 4;
+    fn from_drawing(ss: &[Line]) -> Result<CrateStacks, SolveError> {
+        let labels = ss
+            .last()
+            .ok_or_else(|| SolveError::at_line(1, "expected a drawing of the stacks"))?;
+        let cnt = labels.text.split_whitespace().count();
 
         let mut stacks = Vec::with_capacity(cnt);
 
@@ -40,18 +66,23 @@ impl CrateStacks {
         }
 
         for s in ss.iter().rev().skip(1) {
-            for (i, char) in s.chars().skip(1).step_by(4).enumerate() {
+            for (i, char) in s.text.chars().skip(1).step_by(4).enumerate() {
                 if char.is_alphabetic() {
-                    stacks[i].push(char)
+                    stacks
+                        .get_mut(i)
+                        .ok_or_else(|| {
+                            SolveError::at(s.no, 4 * i + 2, "crate outside of any stack")
+                        })?
+                        .push(char)
                 }
             }
         }
 
-        CrateStacks { stacks }
+        Ok(CrateStacks { stacks })
     }
-    
+
     fn get_stacks_for_op_mut(&mut self, op: &MoveOp) -> (&mut Vec<char>, &mut Vec<char>) {
-        let (src, dst) = if op.from < op.to { 
+        let (src, dst) = if op.from < op.to {
             let (left, right) = self.stacks.split_at_mut(op.to);
             (&mut left[op.from], right.first_mut().unwrap())
         } else {
@@ -62,11 +93,23 @@ impl CrateStacks {
         (src, dst)
     }
 
-    fn execute(&mut self, op: MoveOp, preserve_order: bool) {
-        let (src, dst) = self.get_stacks_for_op_mut(&op);
-        
+    fn execute(&mut self, op: &MoveOp, preserve_order: bool) -> Result<(), SolveError> {
+        for (idx, col) in [(op.from, op.from_col), (op.to, op.to_col)] {
+            if idx >= self.stacks.len() {
+                return Err(SolveError::at(op.line, col, "no such stack"));
+            }
+        }
+        if op.from == op.to {
+            return Ok(());
+        }
+
+        let (src, dst) = self.get_stacks_for_op_mut(op);
+
+        let new_src_len = src.len().checked_sub(op.count).ok_or_else(|| {
+            SolveError::at_line(op.line, format_args!("only {} crates to move", src.len()))
+        })?;
+
         if preserve_order {
-            let new_src_len = src.len() - op.count;
             let sub_stack = &src[new_src_len..];
 
             dst.extend_from_slice(sub_stack);
@@ -76,25 +119,76 @@ impl CrateStacks {
                 dst.push(src.pop().unwrap())
             }
         }
+
+        Ok(())
     }
 }
 
-struct MoveOp {
+pub struct MoveOp {
     from: usize,
     to: usize,
     count: usize,
+
+    line: usize,
+    from_col: usize,
+    to_col: usize,
 }
 
-impl FromStr for MoveOp {
-    type Err = ParseIntError;
+impl MoveOp {
+    fn parse(line: Line) -> Result<MoveOp, SolveError> {
+        let rest = line.strip_prefix(line.text, "move ")?;
+        let (count, rest) = line.split_once(rest, " from ")?;
+        let (from, to) = line.split_once(rest, " to ")?;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ').skip(1).step_by(2).map(|s| s.parse::<usize>());
+        let parse_stack = |s: &str| match line.parse::<usize>(s)? {
+            0 => Err(line.error(s, "stacks are numbered from 1")),
+            v => Ok(v - 1),
+        };
 
         Ok(MoveOp {
-            count: parts.next().unwrap()?,
-            from: parts.next().unwrap()? - 1,
-            to: parts.next().unwrap()? - 1,
+            count: line.parse(count)?,
+            from: parse_stack(from)?,
+            to: parse_stack(to)?,
+            line: line.no,
+            from_col: line.column_of(from),
+            to_col: line.column_of(to),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    #[test]
+    fn example() {
+        let parsed = Solver::parse(EXAMPLE).unwrap();
+        let (stacks, moves) = &parsed;
+        assert_eq!(
+            stacks.stacks,
+            [vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+        );
+
+        let mut rearranged = stacks.clone();
+        for m in moves {
+            rearranged.execute(m, false).unwrap();
+        }
+        assert_eq!(
+            rearranged.stacks,
+            [vec!['C'], vec!['M'], vec!['P', 'D', 'N', 'Z']]
+        );
+
+        assert_eq!(Solver::part1(&parsed).unwrap(), Answer::text("CMZ"));
+        assert_eq!(Solver::part2(&parsed).unwrap(), Answer::text("MCD"));
+    }
+}
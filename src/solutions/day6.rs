@@ -1,38 +1,75 @@
-use crate::Stage;
-
-pub fn solve(stage: Stage, input: &str) -> String {
-    find_sop_marker(
-        input,
-        match stage {
-            Stage::Easy => 4,
-            Stage::Hard => 14,
-        },
-    )
-    .to_string()
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = &'a str;
+
+    fn parse(input: &str) -> Result<&str, SolveError> {
+        Ok(input)
+    }
+
+    fn part1(datastream: &&str) -> Result<Answer, SolveError> {
+        solve_for_marker_len(datastream, 4)
+    }
+
+    fn part2(datastream: &&str) -> Result<Answer, SolveError> {
+        solve_for_marker_len(datastream, 14)
+    }
 }
 
-fn find_sop_marker(s: &str, cnt: usize) -> usize {
+fn solve_for_marker_len(datastream: &str, cnt: usize) -> Result<Answer, SolveError> {
+    let marker = find_sop_marker(datastream, cnt)
+        .ok_or_else(|| SolveError::new("no start-of-packet marker found"))?;
+
+    Ok(marker.into())
+}
+
+fn find_sop_marker(s: &str, cnt: usize) -> Option<usize> {
     let mut buf: Vec<char> = s.chars().take(cnt).collect();
     let mut head = 0;
 
     for (i, c) in s.chars().skip(cnt).enumerate() {
         if is_all_unique(&buf) {
-            return i + cnt;
+            return Some(i + cnt);
         }
 
         buf[head] = c;
         head = (head + 1) % cnt;
     }
 
-    s.len()
+    (buf.len() == cnt && is_all_unique(&buf)).then_some(s.len())
 }
 
-fn is_all_unique(chars: &Vec<char>) -> bool {
+fn is_all_unique(chars: &[char]) -> bool {
     for (i, c1) in chars.iter().enumerate() {
         for c2 in chars.iter().skip(i + 1) {
-            if c1 == c2 { return false }
+            if c1 == c2 {
+                return false;
+            }
         }
     }
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        for (datastream, packet, message) in [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ] {
+            assert_eq!(Solver::part1(&datastream).unwrap(), packet.into());
+            assert_eq!(Solver::part2(&datastream).unwrap(), message.into());
+        }
+    }
+}
@@ -1,24 +1,43 @@
 use std::collections::HashMap;
 
-use crate::Stage;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::parse::lines;
+use crate::solutions::Solution;
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let mut tree = build_tree(input);
-    tree.update_total_size();
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Directory;
+
+    fn parse(input: &str) -> Result<Directory, SolveError> {
+        read_tree(input)
+    }
+
+    fn part1(tree: &Directory) -> Result<Answer, SolveError> {
+        Ok(solve_easy(tree).into())
+    }
 
-    match stage {
-        Stage::Easy => {solve_easy(&tree)}
-        Stage::Hard => {solve_hard(&tree)}
+    fn part2(tree: &Directory) -> Result<Answer, SolveError> {
+        Ok(solve_hard(tree).into())
     }
-        .to_string()
+}
+
+fn read_tree(input: &str) -> Result<Directory, SolveError> {
+    let mut tree = build_tree(input)?;
+    tree.update_total_size();
+
+    Ok(tree)
 }
 
 fn solve_hard(tree: &Directory) -> usize {
-    let required_size = 30000000 - (70000000 - tree.total_size);
-    
+    let required_size = (30000000 + tree.total_size).saturating_sub(70000000);
+
     let mut size = usize::MAX;
     let mut visitor = |dir: &Directory| {
-        if dir.total_size >= required_size && dir.total_size < size { size = dir.total_size }
+        if dir.total_size >= required_size && dir.total_size < size {
+            size = dir.total_size
+        }
     };
 
     tree.visit(&mut visitor);
@@ -28,26 +47,28 @@ fn solve_hard(tree: &Directory) -> usize {
 fn solve_easy(tree: &Directory) -> usize {
     let mut size = 0usize;
     let mut visitor = |dir: &Directory| {
-        if dir.total_size <= 100000 { size += dir.total_size }
+        if dir.total_size <= 100000 {
+            size += dir.total_size
+        }
     };
 
     tree.visit(&mut visitor);
     size
 }
 
-fn build_tree(input: &str) -> Directory {
+fn build_tree(input: &str) -> Result<Directory, SolveError> {
     let mut root = Directory::new();
     root.add_dir("/");
     let mut cwd = &mut root;
 
     let mut stack: Vec<&str> = Vec::new();
 
-    for row in input.lines() {
-        if row == "$ ls" {
+    for row in lines(input) {
+        if row.text == "$ ls" {
             continue;
         }
 
-        let target_dir = row.strip_prefix("$ cd ");
+        let target_dir = row.text.strip_prefix("$ cd ");
         if let Some(target_dir) = target_dir {
             match target_dir {
                 ".." => {
@@ -57,28 +78,28 @@ fn build_tree(input: &str) -> Directory {
                     stack.push(child);
                 }
             }
-            cwd = root.get_child_dir_mut(&stack).unwrap();
+            cwd = root
+                .get_child_dir_mut(&stack)
+                .ok_or_else(|| row.error(target_dir, "no such directory"))?;
 
             continue;
         }
 
-        let [sz_or_dir, name] = row.split(' ').collect::<Vec<&str>>()[..] else {
-            unreachable!()
-        };
+        let (sz_or_dir, name) = row.split_once(row.text, " ")?;
         match sz_or_dir {
             "dir" => {
                 cwd.add_dir(name);
             }
             size => {
-                cwd.add_file(name, size.parse().unwrap());
+                cwd.add_file(name, row.parse(size)?);
             }
         }
     }
 
-    root
+    Ok(root)
 }
 
-struct Directory {
+pub struct Directory {
     total_size: usize,
     files: HashMap<String, usize>,
     sub_dirs: HashMap<String, Directory>,
@@ -98,12 +119,8 @@ impl Directory {
             sub_dir.update_total_size();
         }
 
-        self.total_size = self
-            .sub_dirs
-            .iter()
-            .map(|(_, d)| d.total_size)
-            .sum::<usize>()
-            + self.files.iter().map(|(_, s)| s).sum::<usize>();
+        self.total_size = self.sub_dirs.values().map(|d| d.total_size).sum::<usize>()
+            + self.files.values().sum::<usize>();
     }
 
     fn add_file(&mut self, name: &str, size: usize) {
@@ -125,8 +142,8 @@ impl Directory {
 
         Some(result)
     }
-    
-    fn visit(&self, cb: &mut impl FnMut(&Self) -> ()) {
+
+    fn visit(&self, cb: &mut impl FnMut(&Self)) {
         cb(self);
         for value in self.sub_dirs.values() {
             value.visit(cb);
@@ -160,7 +177,7 @@ impl Directory {
                 }
 
                 let mut base = String::new();
-                
+
                 for _ in 0..level - 1 {
                     base.push('│');
                 }
@@ -170,3 +187,53 @@ impl Directory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn example() {
+        let tree = Solver::parse(EXAMPLE).unwrap();
+
+        let size = |path: &[&str]| {
+            let mut dir = &tree;
+            for name in path {
+                dir = &dir.sub_dirs[*name];
+            }
+            dir.total_size
+        };
+        assert_eq!(size(&["/", "a", "e"]), 584);
+        assert_eq!(size(&["/", "a"]), 94853);
+        assert_eq!(size(&["/", "d"]), 24933642);
+        assert_eq!(size(&["/"]), 48381165);
+
+        assert_eq!(Solver::part1(&tree).unwrap(), 95437.into());
+        assert_eq!(Solver::part2(&tree).unwrap(), 24933642.into());
+    }
+}
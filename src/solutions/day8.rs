@@ -1,115 +1,86 @@
-use std::cmp::max;
-use std::iter;
-
-use crate::Stage;
-
-pub fn solve(stage: Stage, input: &str) -> String {
-    let tree_map: Vec<_> = input.lines().collect();
-    
-    match stage {
-        Stage::Easy => count_visible(&tree_map),
-        Stage::Hard => find_best_spot(&tree_map),
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::grid::{Grid, Pos, ORTHOGONAL};
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Grid<u8>, SolveError> {
+        Grid::parse(input, |c| {
+            c.to_digit(10)
+                .map(|h| h as u8)
+                .ok_or_else(|| format!("expected a tree height, got '{c}'"))
+        })
     }
-    .to_string()
-}
 
-// yeah, it's slow. But who cares? O(N^3) is O(1) if N is constant :-p
-fn find_best_spot(tree_map: &[&str]) -> usize {
-    fn count_visible(
-        tree_map: &[&str],
-        height: u8,
-        i_range: impl IntoIterator<Item = usize> + Clone,
-        j_range: impl IntoIterator<Item = usize> + Clone,
-    ) -> usize {
-        let mut cnt = 0;
-
-        for i in i_range {
-            for j in j_range.clone() {
-                if tree_map[i].as_bytes()[j] < height {
-                    cnt += 1;
-                } else {
-                    return cnt + 1
-                }
-            }
-        }
-
-        cnt
+    fn part1(tree_map: &Grid<u8>) -> Result<Answer, SolveError> {
+        Ok(count_visible(tree_map).into())
     }
 
-    let mut best = 0;
-
-    for i in 1..tree_map.len() - 1 {
-        for j in 1..tree_map[i].len() - 1 {
-            let height = tree_map[i].as_bytes()[j];
-            let left = count_visible(tree_map, height, i..i + 1, (0..j).rev());
-            let right = count_visible(tree_map, height, i..i + 1, j + 1..tree_map[i].len());
-            let up = count_visible(tree_map, height, (0..i).rev(), j..j + 1);
-            let down = count_visible(tree_map, height, i + 1..tree_map.len(), j..j + 1);
-
-            best = max(best, left * right * up * down)
-        }
+    fn part2(tree_map: &Grid<u8>) -> Result<Answer, SolveError> {
+        Ok(find_best_spot(tree_map).into())
     }
-
-    best
 }
 
-// yeah, it could be more DRY. But who cares?
-fn count_visible(tree_map: &[&str]) -> usize {
-    let mut visibility_map: Vec<Vec<bool>> = tree_map
-        .iter()
-        .map(|row| iter::repeat(false).take(row.len()).collect())
-        .collect();
+// yeah, it's slow. But who cares? O(N^3) is O(1) if N is constant :-p
+fn find_best_spot(tree_map: &Grid<u8>) -> usize {
+    tree_map
+        .positions()
+        .map(|pos| {
+            ORTHOGONAL
+                .iter()
+                .map(|&dir| viewing_distance(tree_map, pos, dir))
+                .product()
+        })
+        .max()
+        .unwrap_or(0)
+}
 
-    for i in 0..tree_map.len() {
-        let mut current_height = 0;
-        for j in 0..tree_map[i].len() {
-            let height = tree_map[i].as_bytes()[j];
+// the trees seen from `pos` looking towards `dir`, up to and including the first one as tall
+fn viewing_distance(tree_map: &Grid<u8>, pos: Pos, dir: (isize, isize)) -> usize {
+    let height = tree_map[pos];
+    let mut cnt = 0;
 
-            if height > current_height {
-                visibility_map[i][j] = true;
-            }
-            current_height = max(current_height, height);
+    for p in tree_map.ray(pos, dir) {
+        cnt += 1;
+        if tree_map[p] >= height {
+            break;
         }
     }
 
-    for i in 0..tree_map.len() {
-        let mut current_height = 0;
-        for j in (0..tree_map[i].len()).rev() {
-            let height = tree_map[i].as_bytes()[j];
+    cnt
+}
 
-            if height > current_height {
-                visibility_map[i][j] = true;
-            }
-            current_height = max(current_height, height);
-        }
-    }
+fn count_visible(tree_map: &Grid<u8>) -> usize {
+    tree_map
+        .positions()
+        .filter(|&pos| {
+            ORTHOGONAL
+                .iter()
+                .any(|&dir| tree_map.ray(pos, dir).all(|p| tree_map[p] < tree_map[pos]))
+        })
+        .count()
+}
 
-    for j in 0..tree_map[0].len() {
-        let mut current_height = 0;
-        for i in 0..tree_map.len() {
-            let height = tree_map[i].as_bytes()[j];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            if height > current_height {
-                visibility_map[i][j] = true;
-            }
-            current_height = max(current_height, height);
-        }
-    }
+    const EXAMPLE: &str = "\
+30373
+25512
+65332
+33549
+35390";
 
-    for j in 0..tree_map[0].len() {
-        let mut current_height = 0;
-        for i in (0..tree_map.len()).rev() {
-            let height = tree_map[i].as_bytes()[j];
+    #[test]
+    fn example() {
+        let tree_map = Solver::parse(EXAMPLE).unwrap();
 
-            if height > current_height {
-                visibility_map[i][j] = true;
-            }
-            current_height = max(current_height, height);
-        }
+        assert_eq!(Solver::part1(&tree_map).unwrap(), 21.into());
+        assert_eq!(Solver::part2(&tree_map).unwrap(), 8.into());
     }
-
-    visibility_map
-        .iter()
-        .map(|r| r.iter().filter(|x| **x).count())
-        .sum()
 }
@@ -1,134 +1,147 @@
-use std::cmp::max;
 use std::collections::HashSet;
 use std::ops::RangeInclusive;
-use std::str::FromStr;
 
-use anyhow::anyhow;
-use seq_macro::seq;
-
-use crate::solutions::day9::Direction::{Down, Left, Right, Up};
-use crate::Stage;
+use crate::answer::Answer;
+use crate::error::SolveError;
+use crate::solutions::common::grid::Grid;
+use crate::solutions::common::parse::lines;
+use crate::solutions::common::point::{Direction, Point2};
+use crate::solutions::Solution;
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Parsed<'a> = Vec<(Direction, i32)>;
+
+    fn parse(input: &str) -> Result<Vec<(Direction, i32)>, SolveError> {
+        lines(input)
+            .map(|row| {
+                let (dir, cnt) = row.split_once(row.text, " ")?;
+                Ok((row.parse(dir)?, row.parse(cnt)?))
+            })
+            .collect()
+    }
 
-pub fn solve(stage: Stage, input: &str) -> String {
-    let mut rope = Rope::new(match stage {
-        Stage::Easy => 2,
-        Stage::Hard => 10,
-    });
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    visited.insert(*rope.get_tail_position());
+    fn part1(moves: &Vec<(Direction, i32)>) -> Result<Answer, SolveError> {
+        Ok(count_tail_positions(moves, 2).into())
+    }
 
-    for row in input.lines() {
-        let (dir, cnt) = row.split_at(1);
+    fn part2(moves: &Vec<(Direction, i32)>) -> Result<Answer, SolveError> {
+        Ok(count_tail_positions(moves, 10).into())
+    }
+}
 
-        let dir: Direction = dir.parse().unwrap();
-        let cnt: i32 = cnt.trim_start().parse().unwrap();
+fn count_tail_positions(moves: &[(Direction, i32)], rope_len: usize) -> usize {
+    let mut rope = Rope::new(rope_len);
+    let mut visited: HashSet<Point2<i32>> = HashSet::new();
+    visited.insert(*rope.get_tail_position());
 
+    for &(dir, cnt) in moves {
         for _ in 0..cnt {
             rope.step(dir);
             visited.insert(*rope.get_tail_position());
         }
     }
 
-    visited.len().to_string()
+    visited.len()
 }
 
 struct Rope {
-    links: Vec<(i32, i32)>,
-}
-
-#[derive(Copy, Clone)]
-enum Direction {
-    Left,
-    Up,
-    Right,
-    Down,
+    links: Vec<Point2<i32>>,
 }
 
 impl Rope {
     fn new(len: usize) -> Rope {
         Rope {
-            links: (0..len).map(|_| (0, 0)).collect(),
+            links: vec![Point2::default(); len],
         }
     }
 
-    fn get_tail_position(&self) -> &(i32, i32) {
+    fn get_tail_position(&self) -> &Point2<i32> {
         self.links.last().unwrap()
     }
 
     fn step(&mut self, dir: Direction) {
-        let offset = match dir {
-            Left => (-1, 0),
-            Up => (0, 1),
-            Right => (1, 0),
-            Down => (0, -1),
-        };
-        
-        add_offset(&mut self.links[0], offset);
+        self.links[0] += dir.vec();
 
         for i in 1..self.links.len() {
-            let (head, tail) = {
-                let (head, tail) = self.links.split_at_mut(i);
-                (head.last_mut().unwrap(), tail.first_mut().unwrap())
-            };
-
-            let offset = {
-                let mut v = (head.0 - tail.0, head.1 - tail.1);
-                
-                if max(v.0.abs(), v.1.abs()) < 2 { break; }
+            let (head, tail) = (self.links[i - 1], &mut self.links[i]);
 
-                seq!(N in 0..=1 {
-                    if v.N.abs() == 2 {
-                        v.N /= 2
-                    }
-                });
+            if head.chebyshev_dist(*tail) < 2 {
+                break;
+            }
 
-                v
-            };
-
-            add_offset(tail, offset);
-        }
-        
-        #[inline]
-        fn add_offset(target: &mut (i32, i32), offset: (i32, i32)) {
-            target.0 += offset.0;
-            target.1 += offset.1;
+            // a knot two steps away moves by one, diagonally if needed
+            *tail += (head - *tail).signum();
         }
     }
 
     #[allow(dead_code)]
     fn print(&self, range_x: RangeInclusive<i32>, range_y: RangeInclusive<i32>) {
-        let mut field: Vec<Vec<char>> = range_y
-            .clone()
-            .map(|_| range_x.clone().map(|_| '.').collect())
-            .collect();
-
-        let offset = (-range_x.start(), -range_y.start());
+        let width = range_x.clone().count();
+        let height = range_y.clone().count();
+        let mut field = Grid::new(width, height, '.');
+
+        let to_pos = |p: Point2<i32>| {
+            (
+                (p.y - range_y.start()) as usize,
+                (p.x - range_x.start()) as usize,
+            )
+        };
 
-        field[offset.1 as usize][offset.0 as usize] = 's';
+        field[to_pos(Point2::default())] = 's';
 
-        for (i, (x, y)) in self.links.iter().enumerate().rev() {
-            field[(y + offset.1) as usize][(x + offset.0) as usize] = match i {
+        for (i, &link) in self.links.iter().enumerate().rev() {
+            field[to_pos(link)] = match i {
                 0 => 'H',
                 i => char::from_digit(i as u32, 10).unwrap(),
             };
         }
 
-        for row in field.iter().rev() {
-            println!("{}", String::from_iter(row.iter()));
-        }
+        print!("{}", field.render(|&c| c));
     }
 }
 
-impl FromStr for Direction {
-    type Err = anyhow::Error;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+    const LARGER_EXAMPLE: &str = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20";
+
+    #[test]
+    fn example() {
+        let moves = Solver::parse(EXAMPLE).unwrap();
+
+        assert_eq!(Solver::part1(&moves).unwrap(), 13.into());
+        assert_eq!(Solver::part2(&moves).unwrap(), 1.into());
+    }
+
+    #[test]
+    fn larger_example() {
+        let moves = Solver::parse(LARGER_EXAMPLE).unwrap();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "L" => Ok(Left),
-            "U" => Ok(Up),
-            "R" => Ok(Right),
-            "D" => Ok(Down),
-            _ => Err(anyhow!("Invalid string")),
+        let mut rope = Rope::new(10);
+        for &(dir, cnt) in &moves[..2] {
+            for _ in 0..cnt {
+                rope.step(dir);
+            }
         }
+        // up is towards negative y
+        let knots = [
+            (5, 8),
+            (5, 7),
+            (5, 6),
+            (5, 5),
+            (5, 4),
+            (4, 4),
+            (3, 3),
+            (2, 2),
+            (1, 1),
+            (0, 0),
+        ];
+        assert_eq!(rope.links, knots.map(|(x, y)| Point2::new(x, -y)));
+
+        assert_eq!(Solver::part2(&moves).unwrap(), 36.into());
     }
 }
@@ -1,58 +1,151 @@
 use seq_macro::seq;
 
-use advent_of_code_2022::solutions::{get_solver_for_day, INPUTS};
+use lazy_static::lazy_static;
+
+use advent_of_code_2022::answer::Answer;
+use advent_of_code_2022::inputs::{Embedded, InputSource};
+use advent_of_code_2022::solutions::{registry, DayInfo, Params, UnknownDay};
 use advent_of_code_2022::*;
 
-static ANSWERS: &[[&str; 2]] = &[
-    ["69795", "208437"],
-    ["13268", "15508"],
-    ["7785", "2633"],
-    ["569", "936"],
-    ["RLFNRTNFB", "MHQTLJRLB"],
-    ["1582", "3588"],
-    ["1908462", "3979145"],
-    ["1719", "590824"],
-    ["5779", "2331"],
-    [
-        "14320",
-        "\
-▉▉▉   ▉▉  ▉▉▉  ▉▉▉  ▉  ▉  ▉▉  ▉▉▉    ▉▉ 
-▉  ▉ ▉  ▉ ▉  ▉ ▉  ▉ ▉ ▉  ▉  ▉ ▉  ▉    ▉ 
-▉  ▉ ▉    ▉  ▉ ▉▉▉  ▉▉   ▉  ▉ ▉  ▉    ▉ 
-▉▉▉  ▉    ▉▉▉  ▉  ▉ ▉ ▉  ▉▉▉▉ ▉▉▉     ▉ 
-▉    ▉  ▉ ▉    ▉  ▉ ▉ ▉  ▉  ▉ ▉    ▉  ▉ 
-▉     ▉▉  ▉    ▉▉▉  ▉  ▉ ▉  ▉ ▉     ▉▉  
- ",
-    ],
-    ["151312", "51382025916"],
-    ["481", "480"],
-    ["4821", "21890"],
-    ["1406", "20870"],
-    ["4582667", "10961118625406"],
-    ["1559", "2191"],
-];
+lazy_static! {
+    static ref ANSWERS: Vec<[Answer; 2]> = vec![
+        [69795.into(), 208437.into()],
+        [13268.into(), 15508.into()],
+        [7785.into(), 2633.into()],
+        [569.into(), 936.into()],
+        [Answer::text("RLFNRTNFB"), Answer::text("MHQTLJRLB")],
+        [1582.into(), 3588.into()],
+        [1908462.into(), 3979145.into()],
+        [1719.into(), 590824.into()],
+        [5779.into(), 2331.into()],
+        [14320.into(), Answer::text("PCPBKAPJ")],
+        [151312.into(), 51382025916i64.into()],
+        [481.into(), 480.into()],
+        [4821.into(), 21890.into()],
+        [1406.into(), 20870.into()],
+        [4582667.into(), 10961118625406i64.into()],
+        [1559.into(), 2191.into()],
+        // The inputs of days 17 to 25 are generated rather than real puzzle inputs, so there are
+        // no known answers for them. Only the examples in their unit tests check those days.
+    ];
+}
 
 seq!(N in 1..=25 {
     #[test]
     fn easy_day~N() {
-        if N > ANSWERS.len() {
-            println!("Not solved yet, skipping");
-        } else {
-            assert_eq!(ANSWERS[N - 1][0], compute_answer(N, Stage::Easy));
+        match registry().get(N) {
+            Err(e) => println!("{e}, skipping"),
+            Ok(day) => match ANSWERS.get(day.day as usize - 1) {
+                None => println!("No known answers for day {}, skipping", N),
+                Some(answers) => assert_eq!(answers[0], compute_answer(day, Stage::Easy)),
+            },
         }
     }
 
     #[test]
     fn hard_day~N() {
-        if N > ANSWERS.len() {
-            println!("Not solved yet, skipping");
-        } else {
-            assert_eq!(ANSWERS[N - 1][1], compute_answer(N, Stage::Hard));
+        match registry().get(N) {
+            Err(e) => println!("{e}, skipping"),
+            Ok(day) if !day.is_implemented(Stage::Hard) => {
+                println!("Day {} has one stage, skipping", N)
+            }
+            Ok(day) => match ANSWERS.get(day.day as usize - 1) {
+                None => println!("No known answers for day {}, skipping", N),
+                Some(answers) => assert_eq!(answers[1], compute_answer(day, Stage::Hard)),
+            },
         }
     }
 });
 
+#[test]
+fn registry_rejects_unknown_days() {
+    assert_eq!(registry().get(0).err(), Some(UnknownDay(0)));
+    assert_eq!(registry().get(26).err(), Some(UnknownDay(26)));
+    assert_eq!(registry().days().count(), 25);
+}
+
+#[test]
+fn stage_parsing_is_strict() {
+    assert_eq!("hard".parse::<Stage>().unwrap(), Stage::Hard);
+    assert_eq!("-".parse::<Stage>().unwrap(), Stage::Easy);
+    assert!("".parse::<Stage>().is_err());
+    assert_eq!(
+        "hrad".parse::<Stage>().unwrap_err().to_string(),
+        "unknown stage 'hrad', expected 'easy' (or -, false) or 'hard' (or +, true)"
+    );
+}
+
+#[test]
+fn solve_both_matches_single_stages() {
+    for day in [7, 12] {
+        let day = registry().get(day).unwrap();
+        let input = Embedded.load(day.day).unwrap();
+        let both = day.solve_both(&input).unwrap();
+
+        for stage in Stage::ALL {
+            assert_eq!(
+                both[stage as usize],
+                day.solve(stage, &input).unwrap(),
+                "day {} {stage}",
+                day.day
+            );
+        }
+    }
+}
+
+#[test]
+fn params_change_the_puzzle_settings() {
+    let input = Embedded.load(16).unwrap();
+    let day = registry().get(16).unwrap();
+
+    let mut params = Params::new();
+    params.set("minutes", "30");
+    params.set("elephant-minutes", "30");
+    let parsed = day.parse_with(&input, &params).unwrap();
+    assert_eq!(parsed.solve(Stage::Easy).unwrap(), 1559.into());
+    // with as much time as alone, the elephant can only help
+    assert_eq!(parsed.solve(Stage::Hard).unwrap(), 2908.into());
+
+    // the days without settings don't quietly ignore them
+    let err = registry()
+        .get(1)
+        .unwrap()
+        .parse_with(&Embedded.load(1).unwrap(), &params)
+        .err()
+        .unwrap();
+    assert_eq!(err.day, Some(1));
+}
+
+#[test]
+fn malformed_input_reports_location() {
+    let location = |day: u8, input: &str| {
+        let err = registry()
+            .get(day)
+            .unwrap()
+            .solve(Stage::Easy, input)
+            .unwrap_err();
+        (err.day, err.line, err.column)
+    };
+
+    assert_eq!(location(9, "R 4\nU x"), (Some(9), Some(2), Some(3)));
+    assert_eq!(
+        location(
+            5,
+            "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 5 from 2 to 1"
+        ),
+        (Some(5), Some(6), None)
+    );
+    assert_eq!(location(13, "[1,[2]\n[3]"), (Some(13), Some(1), Some(7)));
+    assert_eq!(
+        location(16, "Valve AA has flow rate=0; tunnel leads to valve BB"),
+        (Some(16), Some(1), Some(49))
+    );
+}
+
 #[ignore]
-fn compute_answer(day: u8, stage: Stage) -> String {
-    get_solver_for_day(day)(stage, INPUTS[(day - 1) as usize])
+fn compute_answer(day: &DayInfo, stage: Stage) -> Answer {
+    let answer = day.solve(stage, &Embedded.load(day.day).unwrap()).unwrap();
+    assert_eq!(answer.answer_type(), day.answer_type(stage));
+
+    answer
 }